@@ -0,0 +1,191 @@
+use crossterm::{
+    cursor::MoveTo,
+    queue,
+    style::{Attribute, Color, Print, SetAttribute, SetForegroundColor},
+};
+use std::{collections::HashMap, io::Write};
+
+use crate::charset::char_width;
+
+/// Text attributes applied to a single cell
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CellAttrs {
+    pub bold: bool,
+    pub dim: bool,
+}
+
+/// A single character cell on the screen
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cell {
+    pub ch: char,
+    pub fg: Color,
+    pub attrs: CellAttrs,
+}
+
+impl Cell {
+    /// An empty cell with the terminal's default color
+    pub const BLANK: Cell = Cell {
+        ch: ' ',
+        fg: Color::Reset,
+        attrs: CellAttrs { bold: false, dim: false },
+    };
+
+    pub fn new(ch: char, fg: Color) -> Self {
+        Self { ch, fg, attrs: CellAttrs::default() }
+    }
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self::BLANK
+    }
+}
+
+/// Double-buffered cell grid
+///
+/// The simulation draws into the back buffer each frame, and `flush` only
+/// emits the cells that differ from what is already on the terminal (the
+/// front buffer). Changed cells are grouped by style so each color and
+/// attribute change is written once per frame rather than once per cell.
+///
+/// A wide glyph covers the cell to its right as well, so that cell is never
+/// written while the glyph is on screen.
+pub struct ScreenBuffer {
+    width: u16,
+    height: u16,
+    front: Vec<Cell>,
+    back: Vec<Cell>,
+}
+
+impl ScreenBuffer {
+    pub fn new(width: u16, height: u16) -> Self {
+        let len = width as usize * height as usize;
+        Self {
+            width,
+            height,
+            front: vec![Cell::BLANK; len],
+            back: vec![Cell::BLANK; len],
+        }
+    }
+
+    /// Resize both buffers, assuming the terminal itself has been cleared
+    pub fn resize(&mut self, width: u16, height: u16) {
+        *self = Self::new(width, height);
+    }
+
+    /// Reset the back buffer to blank cells
+    pub fn clear(&mut self) {
+        self.back.fill(Cell::BLANK);
+    }
+
+    fn index(&self, x: u16, y: u16) -> Option<usize> {
+        if x < self.width && y < self.height {
+            Some(y as usize * self.width as usize + x as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Write a cell into the back buffer, ignoring out-of-bounds positions
+    pub fn set(&mut self, x: u16, y: u16, cell: Cell) {
+        if let Some(i) = self.index(x, y) {
+            self.back[i] = cell;
+        }
+    }
+
+    /// Whether the cell at `i` is hidden under a wide glyph to its left
+    fn covered(&self, i: usize) -> bool {
+        !i.is_multiple_of(self.width as usize) && char_width(self.back[i - 1].ch) > 1
+    }
+
+    /// Emit every changed cell and swap the back buffer to the front
+    pub fn flush(&mut self, w: &mut impl Write) -> std::io::Result<()> {
+        // Group changed cells by style, keeping groups in first-seen order
+        let mut groups: Vec<((Color, CellAttrs), Vec<usize>)> = Vec::new();
+        let mut group_index: HashMap<(Color, CellAttrs), usize> = HashMap::new();
+
+        let width = self.width as usize;
+        for (i, (back, front)) in self.back.iter().zip(self.front.iter()).enumerate() {
+            if back == front || self.covered(i) {
+                continue;
+            }
+            // Blank cells print a space, so their color never matters
+            let style = if back.ch == ' ' && back.attrs == CellAttrs::default() {
+                (Color::Reset, CellAttrs::default())
+            } else {
+                (back.fg, back.attrs)
+            };
+            let g = *group_index.entry(style).or_insert_with(|| {
+                groups.push((style, Vec::new()));
+                groups.len() - 1
+            });
+            groups[g].1.push(i);
+        }
+
+        if groups.is_empty() {
+            return Ok(());
+        }
+
+        let mut cursor: Option<usize> = None;
+        let mut current_attrs = CellAttrs::default();
+
+        for ((fg, attrs), indices) in &groups {
+            if *attrs != current_attrs {
+                queue!(w, SetAttribute(Attribute::Reset))?;
+                if attrs.bold {
+                    queue!(w, SetAttribute(Attribute::Bold))?;
+                }
+                if attrs.dim {
+                    queue!(w, SetAttribute(Attribute::Dim))?;
+                }
+                current_attrs = *attrs;
+            }
+            set_foreground(w, *fg)?;
+
+            for &i in indices {
+                if cursor != Some(i) {
+                    queue!(w, MoveTo((i % width) as u16, (i / width) as u16))?;
+                }
+                queue!(w, Print(self.back[i].ch))?;
+                // The cursor advances by the glyph's width, but not past the end of a row
+                let next = i + char_width(self.back[i].ch).max(1);
+                cursor = if next / width != i / width { None } else { Some(next) };
+            }
+        }
+
+        if current_attrs != CellAttrs::default() {
+            queue!(w, SetAttribute(Attribute::Reset))?;
+        }
+
+        self.front.copy_from_slice(&self.back);
+        Ok(())
+    }
+}
+
+/// Set the foreground color, using the classic 30-37/90-97 codes for the
+/// basic colors so 8- and 16-color terminals understand them
+fn set_foreground(w: &mut impl Write, color: Color) -> std::io::Result<()> {
+    let code = match color {
+        Color::Reset => 39,
+        Color::Black => 30,
+        Color::DarkRed => 31,
+        Color::DarkGreen => 32,
+        Color::DarkYellow => 33,
+        Color::DarkBlue => 34,
+        Color::DarkMagenta => 35,
+        Color::DarkCyan => 36,
+        Color::Grey => 37,
+        Color::DarkGrey => 90,
+        Color::Red => 91,
+        Color::Green => 92,
+        Color::Yellow => 93,
+        Color::Blue => 94,
+        Color::Magenta => 95,
+        Color::Cyan => 96,
+        Color::White => 97,
+        Color::Rgb { .. } | Color::AnsiValue(_) => {
+            return queue!(w, SetForegroundColor(color));
+        }
+    };
+    write!(w, "\x1b[{code}m")
+}
@@ -0,0 +1,257 @@
+use crossterm::style::Color;
+use std::{env, fs, path::PathBuf, time::Duration};
+
+use crate::colors::{ansi_color, color_to_rgb, nearest_ansi_256};
+
+/// Index of the `colors` capability in a compiled terminfo entry
+const TERMINFO_COLORS: usize = 13;
+/// Magic numbers of the legacy (16-bit) and extended (32-bit) terminfo formats
+const TERMINFO_MAGIC_16: u16 = 0o432;
+const TERMINFO_MAGIC_32: u16 = 0o1036;
+
+/// How many colors the terminal can display
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorDepth {
+    /// 24-bit RGB
+    #[default]
+    TrueColor,
+    /// The xterm 256-color palette
+    Ansi256,
+    /// The 16 basic ANSI colors
+    Ansi16,
+    /// No color at all
+    Mono,
+}
+
+/// A `--color-depth` value: detect the terminal's depth or force one
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorDepthChoice {
+    #[default]
+    Auto,
+    Fixed(ColorDepth),
+}
+
+impl ColorDepthChoice {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        if name.trim().eq_ignore_ascii_case("auto") {
+            return Ok(Self::Auto);
+        }
+        ColorDepth::parse(name).map(Self::Fixed).map_err(|_| {
+            format!(
+                "unknown color depth '{}', expected one of: auto, {}",
+                name.trim(),
+                ColorDepth::NAMES.join(", ")
+            )
+        })
+    }
+
+    /// The forced depth, or `None` to detect it
+    pub fn depth(self) -> Option<ColorDepth> {
+        match self {
+            Self::Auto => None,
+            Self::Fixed(depth) => Some(depth),
+        }
+    }
+}
+
+impl ColorDepth {
+    pub const NAMES: &[&str] = &["truecolor", "256", "16", "mono"];
+
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "truecolor" | "24bit" | "rgb" => Ok(Self::TrueColor),
+            "256" | "ansi256" => Ok(Self::Ansi256),
+            "16" | "8" | "ansi16" | "ansi" => Ok(Self::Ansi16),
+            "mono" | "none" | "2" => Ok(Self::Mono),
+            other => Err(format!(
+                "unknown color depth '{other}', expected one of: {}",
+                Self::NAMES.join(", ")
+            )),
+        }
+    }
+
+    /// Detect the current terminal's color support from `COLORTERM`, `TERM`
+    /// and its terminfo entry
+    pub fn detect() -> Self {
+        let colorterm = env::var("COLORTERM").ok();
+        let term = env::var("TERM").ok();
+        let colors = term.as_deref().and_then(read_terminfo).and_then(|data| terminfo_colors(&data));
+        Self::from_env(colorterm.as_deref(), term.as_deref(), colors)
+    }
+
+    /// Decide the depth from the environment and the terminfo `colors` count
+    pub fn from_env(colorterm: Option<&str>, term: Option<&str>, terminfo_colors: Option<i32>) -> Self {
+        if let Some(colorterm) = colorterm {
+            let colorterm = colorterm.to_ascii_lowercase();
+            if colorterm == "truecolor" || colorterm == "24bit" {
+                return Self::TrueColor;
+            }
+        }
+
+        let Some(term) = term.map(str::to_ascii_lowercase) else {
+            // Windows terminals rarely set TERM but all support truecolor
+            return if cfg!(windows) { Self::TrueColor } else { Self::Mono };
+        };
+        if term.is_empty() || term == "dumb" {
+            return Self::Mono;
+        }
+        if term.contains("truecolor") || term.contains("24bit") || term.ends_with("-direct") {
+            return Self::TrueColor;
+        }
+
+        match terminfo_colors {
+            Some(n) if n >= 1 << 24 => Self::TrueColor,
+            Some(n) if n >= 256 => Self::Ansi256,
+            Some(n) if n >= 8 => Self::Ansi16,
+            Some(_) => Self::Mono,
+            // No terminfo entry: guess from the name
+            None if term.contains("256color") => Self::Ansi256,
+            None => Self::Ansi16,
+        }
+    }
+
+    /// Map a color to the closest one this depth can display
+    ///
+    /// Mono maps everything to the terminal's default color.
+    pub fn quantize(self, color: Color) -> Color {
+        match self {
+            Self::TrueColor => color,
+            Self::Ansi256 => match color {
+                Color::Rgb { r, g, b } => Color::AnsiValue(nearest_ansi_256((r, g, b))),
+                other => other,
+            },
+            Self::Ansi16 => match color {
+                Color::Rgb { .. } | Color::AnsiValue(_) => match color_to_rgb(color) {
+                    Some(rgb) => ansi_color(nearest_ansi_16(rgb)),
+                    None => color,
+                },
+                other => other,
+            },
+            Self::Mono => Color::Reset,
+        }
+    }
+}
+
+/// Closest of the 16 basic ANSI colors to an RGB value
+pub fn nearest_ansi_16((r, g, b): (u8, u8, u8)) -> u8 {
+    (0..16)
+        .min_by_key(|&code| {
+            let (cr, cg, cb) = color_to_rgb(ansi_color(code)).unwrap_or((0, 0, 0));
+            let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+            d(r, cr) + d(g, cg) + d(b, cb)
+        })
+        .unwrap_or(0)
+}
+
+/// Read the `colors` number from a compiled terminfo entry
+///
+/// Returns `None` for malformed data or when the capability is absent.
+pub fn terminfo_colors(data: &[u8]) -> Option<i32> {
+    let short = |i: usize| -> Option<u16> {
+        data.get(i..i + 2).map(|b| u16::from_le_bytes([b[0], b[1]]))
+    };
+
+    let magic = short(0)?;
+    let number_size = match magic {
+        TERMINFO_MAGIC_16 => 2,
+        TERMINFO_MAGIC_32 => 4,
+        _ => return None,
+    };
+    let names_size = short(2)? as usize;
+    let bools_count = short(4)? as usize;
+    let numbers_count = short(6)? as usize;
+    if numbers_count <= TERMINFO_COLORS {
+        return None;
+    }
+
+    // Numbers start on an even offset after the names and booleans
+    let mut offset = 12 + names_size + bools_count;
+    offset += offset % 2;
+    let at = offset + TERMINFO_COLORS * number_size;
+    let value = match number_size {
+        2 => short(at)? as i16 as i32,
+        _ => data.get(at..at + 4).map(|b| i32::from_le_bytes([b[0], b[1], b[2], b[3]]))?,
+    };
+    (value >= 0).then_some(value)
+}
+
+/// Find and read the compiled terminfo entry for a terminal name
+fn read_terminfo(term: &str) -> Option<Vec<u8>> {
+    let first = term.chars().next()?;
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    if let Some(dir) = env::var_os("TERMINFO") {
+        dirs.push(dir.into());
+    }
+    if let Some(home) = env::var_os("HOME") {
+        dirs.push(PathBuf::from(home).join(".terminfo"));
+    }
+    if let Ok(list) = env::var("TERMINFO_DIRS") {
+        dirs.extend(list.split(':').filter(|d| !d.is_empty()).map(PathBuf::from));
+    }
+    dirs.extend(["/etc/terminfo", "/lib/terminfo", "/usr/share/terminfo"].map(PathBuf::from));
+
+    dirs.iter().find_map(|dir| {
+        // Entries live under their first letter, or its hex code on macOS
+        [first.to_string(), format!("{:x}", first as u32)]
+            .iter()
+            .find_map(|sub| fs::read(dir.join(sub).join(term)).ok())
+    })
+}
+
+/// Ask the terminal for its background color with an OSC 11 query
+///
+/// The terminal must already be in raw mode. Returns `None` if it does not
+/// answer within `timeout`, or on platforms without `/dev/tty`.
+#[cfg(unix)]
+pub fn query_background(timeout: Duration) -> Option<(u8, u8, u8)> {
+    use std::io::{Read, Write};
+    use std::os::fd::AsRawFd;
+    use std::time::Instant;
+
+    let mut tty = fs::OpenOptions::new().read(true).write(true).open("/dev/tty").ok()?;
+    tty.write_all(b"\x1b]11;?\x07").ok()?;
+    tty.flush().ok()?;
+
+    let deadline = Instant::now() + timeout;
+    let mut response = Vec::new();
+    let mut chunk = [0u8; 64];
+    while let Some(left) = deadline.checked_duration_since(Instant::now()) {
+        let mut fds = libc::pollfd { fd: tty.as_raw_fd(), events: libc::POLLIN, revents: 0 };
+        // SAFETY: `fds` is a single valid pollfd that outlives the call
+        let ready = unsafe { libc::poll(&mut fds, 1, left.as_millis().max(1) as libc::c_int) };
+        if ready <= 0 {
+            break;
+        }
+        let n = tty.read(&mut chunk).ok()?;
+        if n == 0 {
+            break;
+        }
+        response.extend_from_slice(&chunk[..n]);
+        // Replies end with BEL or ST
+        if response.ends_with(b"\x07") || response.ends_with(b"\x1b\\") {
+            break;
+        }
+    }
+    parse_osc11(&String::from_utf8_lossy(&response))
+}
+
+#[cfg(not(unix))]
+pub fn query_background(_timeout: Duration) -> Option<(u8, u8, u8)> {
+    None
+}
+
+/// Parse an OSC 11 reply such as `ESC ] 11 ; rgb:ffff/ffff/dddd BEL`
+///
+/// Each channel has one to four hex digits and is scaled to 0-255.
+pub fn parse_osc11(reply: &str) -> Option<(u8, u8, u8)> {
+    let start = reply.find("rgb:")? + 4;
+    let body = reply[start..].trim_end_matches(['\x07', '\x1b', '\\']);
+    let mut channels = body.split('/').map(|hex| {
+        let hex = hex.get(..hex.len().min(4))?;
+        let value = u32::from_str_radix(hex, 16).ok()?;
+        let max = (1u32 << (4 * hex.len())) - 1;
+        Some(((value * 255 + max / 2) / max) as u8)
+    });
+    let rgb = (channels.next()??, channels.next()??, channels.next()??);
+    channels.next().is_none().then_some(rgb)
+}
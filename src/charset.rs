@@ -0,0 +1,192 @@
+use rand::prelude::IndexedRandom;
+use rand::{Rng, RngExt};
+use unicode_width::UnicodeWidthChar;
+
+/// Terminal cells a character takes up (0 for combining and control
+/// characters, 2 for wide CJK and emoji)
+pub fn char_width(c: char) -> usize {
+    c.width().unwrap_or(0)
+}
+
+/// Whether a character can be drawn in a cell of its own
+pub fn is_drawable(c: char) -> bool {
+    !c.is_whitespace() && char_width(c) > 0
+}
+
+/// Whether every terminal draws a character one cell wide
+///
+/// Wide characters take two cells, and East Asian "ambiguous" ones (box
+/// drawing, Greek, Cyrillic...) take two in CJK locales. Halfwidth katakana
+/// are narrow on paper but drawn wide by a number of fonts.
+pub fn is_reliably_narrow(c: char) -> bool {
+    c.width() == Some(1) && c.width_cjk() == Some(1) && !HALFWIDTH_FORMS.contains(&c)
+}
+
+/// Halfwidth katakana and Hangul
+const HALFWIDTH_FORMS: std::ops::RangeInclusive<char> = '\u{FF61}'..='\u{FFDC}';
+
+/// Narrow glyphs used by `narrow_fallback` for characters with no close match
+const NARROW_FALLBACK_CHARS: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// A character every terminal draws one cell wide standing in for `c`
+///
+/// Full-width ASCII maps to plain ASCII, lines and blocks to ASCII art, and
+/// anything else to a letter or digit picked from its code point, so the
+/// same glyph always gets the same stand-in.
+pub fn narrow_fallback(c: char) -> char {
+    if is_reliably_narrow(c) {
+        return c;
+    }
+    match c as u32 {
+        // Full-width forms of printable ASCII
+        code @ 0xFF01..=0xFF5E => char::from_u32(code - 0xFEE0).unwrap_or(c),
+        0x2500..=0x2501 | 0x2504..=0x2505 | 0x2508..=0x2509 | 0x254C..=0x254D | 0x2550 => '-',
+        0x2502..=0x2503 | 0x2506..=0x2507 | 0x250A..=0x250B | 0x254E..=0x254F | 0x2551 => '|',
+        0x2500..=0x257F => '+',
+        0x2580..=0x259F => '#',
+        code => NARROW_FALLBACK_CHARS[code as usize % NARROW_FALLBACK_CHARS.len()] as char,
+    }
+}
+
+/// The characters drops are made of
+///
+/// A charset is one or more groups of characters with relative weights.
+/// Picking a character first picks a group by weight, then a character
+/// from that group uniformly, so a small group can still show up often.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Charset {
+    groups: Vec<(Vec<char>, u32)>,
+    total_weight: u32,
+}
+
+impl Charset {
+    /// A single group of equally likely characters
+    pub fn new(chars: impl Into<Vec<char>>) -> Self {
+        let chars = chars.into();
+        Self { groups: vec![(chars, 1)], total_weight: 1 }
+    }
+
+    /// Weighted groups; empty groups and zero weights are dropped
+    pub fn weighted(groups: Vec<(Vec<char>, u32)>) -> Self {
+        let groups: Vec<_> = groups
+            .into_iter()
+            .filter(|(chars, weight)| !chars.is_empty() && *weight > 0)
+            .collect();
+        let total_weight = groups.iter().map(|(_, weight)| weight).sum();
+        Self { groups, total_weight }
+    }
+
+    /// Use the characters of a string as the charset, e.g. `--chars 01`
+    ///
+    /// Whitespace, control and zero-width characters are skipped, as they
+    /// would draw as gaps in the rain or merge into their neighbours.
+    pub fn from_chars(text: &str) -> Result<Self, String> {
+        let chars: Vec<char> = text.chars().filter(|&c| is_drawable(c)).collect();
+        if chars.is_empty() {
+            return Err("the character set needs at least one visible character".to_string());
+        }
+        Ok(Self::new(chars))
+    }
+
+    /// Parse a palette composition such as `katakana+greek` or
+    /// `katakana:3,digits:1`
+    ///
+    /// Comma-separated parts become separately weighted groups (weight 1
+    /// unless given after `:`), and names joined with `+` are merged into
+    /// one group. `lookup` resolves a single palette name.
+    pub fn parse(
+        spec: &str,
+        lookup: impl Fn(&str) -> Result<Vec<char>, String>,
+    ) -> Result<Self, String> {
+        let mut groups = Vec::new();
+        for part in spec.split(',') {
+            let part = part.trim();
+            let (names, weight) = match part.rsplit_once(':') {
+                Some((names, weight)) => (names, parse_weight(weight)?),
+                None => (part, 1),
+            };
+
+            let mut chars = Vec::new();
+            for name in names.split('+') {
+                let name = name.trim();
+                if name.is_empty() {
+                    return Err(format!("empty palette name in '{spec}'"));
+                }
+                chars.extend(lookup(name)?);
+            }
+            groups.push((chars, weight));
+        }
+
+        let charset = Self::weighted(groups);
+        if charset.is_empty() {
+            return Err(format!("palette '{spec}' has no characters"));
+        }
+        Ok(charset)
+    }
+
+    /// Pick a random character
+    ///
+    /// # Panics
+    /// If the charset is empty.
+    pub fn choose(&self, rng: &mut impl Rng) -> char {
+        let group = match self.groups.as_slice() {
+            [(chars, _)] => chars,
+            groups => {
+                let mut pick = rng.random_range(0..self.total_weight);
+                let index = groups
+                    .iter()
+                    .position(|(_, weight)| {
+                        let hit = pick < *weight;
+                        pick = pick.saturating_sub(*weight);
+                        hit
+                    })
+                    .unwrap_or(0);
+                &groups[index].0
+            }
+        };
+        *group.choose(rng).expect("charset is never empty")
+    }
+
+    /// The groups with their weights
+    pub fn groups(&self) -> &[(Vec<char>, u32)] {
+        &self.groups
+    }
+
+    /// Every character in the charset, group by group
+    pub fn chars(&self) -> impl Iterator<Item = char> + '_ {
+        self.groups.iter().flat_map(|(chars, _)| chars.iter().copied())
+    }
+
+    /// Cells the widest character takes up, 1 for an empty charset
+    pub fn width(&self) -> usize {
+        self.chars().map(char_width).max().unwrap_or(1)
+    }
+
+    /// Total number of characters across all groups
+    pub fn len(&self) -> usize {
+        self.groups.iter().map(|(chars, _)| chars.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.groups.iter().all(|(chars, _)| chars.is_empty())
+    }
+}
+
+impl From<Vec<char>> for Charset {
+    fn from(chars: Vec<char>) -> Self {
+        Self::new(chars)
+    }
+}
+
+impl From<&[char]> for Charset {
+    fn from(chars: &[char]) -> Self {
+        Self::new(chars)
+    }
+}
+
+fn parse_weight(weight: &str) -> Result<u32, String> {
+    match weight.trim().parse::<u32>() {
+        Ok(weight) if weight > 0 => Ok(weight),
+        _ => Err(format!("invalid palette weight '{}', expected a whole number above 0", weight.trim())),
+    }
+}
@@ -8,33 +8,14 @@ pub enum MatrixColorScheme {
 }
 
 impl MatrixColorScheme {
-    /// Create a color scheme from an ANSI color code (0-15)
-    pub fn from_ansi_code(code: u8) -> Self {
-        let color = match code {
-            0 => Color::Black,
-            1 => Color::DarkRed,
-            2 => Color::DarkGreen,
-            3 => Color::DarkYellow,
-            4 => Color::DarkBlue,
-            5 => Color::DarkMagenta,
-            6 => Color::DarkCyan,
-            7 => Color::Grey,
-            8 => Color::DarkGrey,
-            9 => Color::Red,
-            10 => Color::Green,
-            11 => Color::Yellow,
-            12 => Color::Blue,
-            13 => Color::Magenta,
-            14 => Color::Cyan,
-            15 => Color::White,
-            _ => Color::Green, // Default to green for invalid codes
-        };
-        
-        // Use the classic green scheme for green colors, otherwise use custom
-        if matches!(color, Color::Green | Color::DarkGreen) {
-            Self::Green
-        } else {
-            Self::Custom(color)
+    /// Parse a color scheme from the `--color` syntax
+    ///
+    /// Accepts ANSI codes 0-15, 256-color indices 16-255, `#rrggbb` / `#rgb`
+    /// hex, `rgb(r, g, b)` and CSS/X11 color names.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        match parse_color(spec)? {
+            Color::Green | Color::DarkGreen => Ok(Self::Green),
+            color => Ok(Self::Custom(color)),
         }
     }
     
@@ -61,35 +42,398 @@ impl MatrixColorScheme {
     pub fn get_base_rgb(self) -> (u8, u8, u8) {
         match self {
             Self::Green => (0, 255, 0),
-            Self::Custom(color) => {
-                // For custom colors in RGB fade mode, approximate the RGB values
-                match color {
-                    Color::Red => (255, 0, 0),
-                    Color::DarkRed => (139, 0, 0),
-                    Color::Blue => (0, 0, 255),
-                    Color::DarkBlue => (0, 0, 139),
-                    Color::Yellow => (255, 255, 0),
-                    Color::DarkYellow => (184, 134, 11),
-                    Color::Magenta => (255, 0, 255),
-                    Color::DarkMagenta => (139, 0, 139),
-                    Color::Cyan => (0, 255, 255),
-                    Color::DarkCyan => (0, 139, 139),
-                    Color::White => (255, 255, 255),
-                    Color::Grey => (192, 192, 192),
-                    Color::DarkGrey => (169, 169, 169),
-                    Color::Black => (0, 0, 0),
-                    _ => (0, 255, 0), // Default to green
-                }
-            }
+            // Truecolor and 256-color schemes are exact, basic colors approximated
+            Self::Custom(color) => color_to_rgb(color).unwrap_or((0, 255, 0)),
+        }
+    }
+}
+
+/// The crossterm color for an ANSI color code (0-15)
+pub fn ansi_color(code: u8) -> Color {
+    match code {
+        0 => Color::Black,
+        1 => Color::DarkRed,
+        2 => Color::DarkGreen,
+        3 => Color::DarkYellow,
+        4 => Color::DarkBlue,
+        5 => Color::DarkMagenta,
+        6 => Color::DarkCyan,
+        7 => Color::Grey,
+        8 => Color::DarkGrey,
+        9 => Color::Red,
+        10 => Color::Green,
+        11 => Color::Yellow,
+        12 => Color::Blue,
+        13 => Color::Magenta,
+        14 => Color::Cyan,
+        15 => Color::White,
+        _ => Color::Green, // Default to green for invalid codes
+    }
+}
+
+/// RGB components of a color, or `None` for the terminal default
+pub fn color_to_rgb(color: Color) -> Option<(u8, u8, u8)> {
+    let rgb = match color {
+        Color::Reset => return None,
+        Color::Rgb { r, g, b } => (r, g, b),
+        Color::AnsiValue(code) => ansi_256_to_rgb(code),
+        Color::Red => (255, 0, 0),
+        Color::DarkRed => (139, 0, 0),
+        Color::Green => (0, 255, 0),
+        Color::DarkGreen => (0, 139, 0),
+        Color::Blue => (0, 0, 255),
+        Color::DarkBlue => (0, 0, 139),
+        Color::Yellow => (255, 255, 0),
+        Color::DarkYellow => (184, 134, 11),
+        Color::Magenta => (255, 0, 255),
+        Color::DarkMagenta => (139, 0, 139),
+        Color::Cyan => (0, 255, 255),
+        Color::DarkCyan => (0, 139, 139),
+        Color::White => (255, 255, 255),
+        Color::Grey => (192, 192, 192),
+        Color::DarkGrey => (169, 169, 169),
+        Color::Black => (0, 0, 0),
+    };
+    Some(rgb)
+}
+
+/// Levels used by each axis of the xterm 6x6x6 color cube
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// RGB components of an entry in the standard xterm 256-color palette
+pub fn ansi_256_to_rgb(code: u8) -> (u8, u8, u8) {
+    match code {
+        0..=15 => color_to_rgb(ansi_color(code)).unwrap_or((0, 0, 0)),
+        16..=231 => {
+            let i = code - 16;
+            (
+                CUBE_LEVELS[(i / 36) as usize],
+                CUBE_LEVELS[(i / 6 % 6) as usize],
+                CUBE_LEVELS[(i % 6) as usize],
+            )
+        }
+        232..=255 => {
+            let level = 8 + 10 * (code - 232);
+            (level, level, level)
         }
     }
 }
 
+/// Parse a single color in any `--color` syntax
+///
+/// ANSI codes 0-15 become basic colors and 16-255 palette indices; all
+/// other forms become truecolor.
+pub fn parse_color(spec: &str) -> Result<Color, String> {
+    let spec = spec.trim();
+    if let Ok(code) = spec.parse::<u32>() {
+        return match code {
+            0..=15 => Ok(ansi_color(code as u8)),
+            16..=255 => Ok(Color::AnsiValue(code as u8)),
+            _ => Err(format!("color code {code} is out of range (0-255)")),
+        };
+    }
+    let (r, g, b) = parse_rgb(spec)?;
+    Ok(Color::Rgb { r, g, b })
+}
+
+/// Closest entry in the 256-color palette, skipping the 16 basic colors
+/// whose exact values depend on the terminal theme
+pub fn nearest_ansi_256((r, g, b): (u8, u8, u8)) -> u8 {
+    let cube_index = |v: u8| -> usize {
+        CUBE_LEVELS
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, level)| (**level as i32 - v as i32).abs())
+            .map(|(i, _)| i)
+            .unwrap_or(0)
+    };
+    let (ri, gi, bi) = (cube_index(r), cube_index(g), cube_index(b));
+    let cube = 16 + (36 * ri + 6 * gi + bi) as u8;
+
+    // Gray ramp entry closest to the average brightness
+    let avg = (r as u32 + g as u32 + b as u32) / 3;
+    let gray = 232 + ((avg.saturating_sub(3)) / 10).min(23) as u8;
+
+    let distance = |code: u8| {
+        let (cr, cg, cb) = ansi_256_to_rgb(code);
+        let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+        d(r, cr) + d(g, cg) + d(b, cb)
+    };
+    if distance(gray) < distance(cube) { gray } else { cube }
+}
+
+/// Parse `#rrggbb`, `#rgb`, `rgb(r, g, b)` or a CSS/X11 color name
+pub fn parse_rgb(spec: &str) -> Result<(u8, u8, u8), String> {
+    let spec = spec.trim();
+
+    if let Some(hex) = spec.strip_prefix('#') {
+        return parse_hex(hex).ok_or_else(|| format!("invalid hex color '{spec}'"));
+    }
+
+    let lower = spec.to_ascii_lowercase();
+    if let Some(args) = lower.strip_prefix("rgb(").and_then(|rest| rest.strip_suffix(')')) {
+        let parts: Vec<&str> = args.split(',').map(str::trim).collect();
+        if let [r, g, b] = parts[..]
+            && let (Ok(r), Ok(g), Ok(b)) = (r.parse(), g.parse(), b.parse())
+        {
+            return Ok((r, g, b));
+        }
+        return Err(format!("invalid rgb() color '{spec}', expected rgb(0-255, 0-255, 0-255)"));
+    }
+
+    // Names are matched ignoring case, spaces, dashes and underscores
+    let name: String = lower.chars().filter(|c| !matches!(c, ' ' | '-' | '_')).collect();
+    NAMED_COLORS
+        .binary_search_by(|(n, _)| n.cmp(&name.as_str()))
+        .map(|i| NAMED_COLORS[i].1)
+        .map_err(|_| format!("unknown color '{spec}'"))
+}
+
+fn parse_hex(hex: &str) -> Option<(u8, u8, u8)> {
+    if !hex.is_ascii() {
+        return None;
+    }
+    match hex.len() {
+        6 => Some((
+            u8::from_str_radix(&hex[0..2], 16).ok()?,
+            u8::from_str_radix(&hex[2..4], 16).ok()?,
+            u8::from_str_radix(&hex[4..6], 16).ok()?,
+        )),
+        3 => {
+            // Each digit is doubled, so #0f4 means #00ff44
+            let digit = |i: usize| u8::from_str_radix(&hex[i..=i], 16).ok().map(|d| d * 17);
+            Some((digit(0)?, digit(1)?, digit(2)?))
+        }
+        _ => None,
+    }
+}
+
+/// CSS color names plus the unnumbered X11 extras, sorted for binary search
+const NAMED_COLORS: &[(&str, (u8, u8, u8))] = &[
+    ("aliceblue", (240, 248, 255)),
+    ("antiquewhite", (250, 235, 215)),
+    ("aqua", (0, 255, 255)),
+    ("aquamarine", (127, 255, 212)),
+    ("azure", (240, 255, 255)),
+    ("beige", (245, 245, 220)),
+    ("bisque", (255, 228, 196)),
+    ("black", (0, 0, 0)),
+    ("blanchedalmond", (255, 235, 205)),
+    ("blue", (0, 0, 255)),
+    ("blueviolet", (138, 43, 226)),
+    ("brown", (165, 42, 42)),
+    ("burlywood", (222, 184, 135)),
+    ("cadetblue", (95, 158, 160)),
+    ("chartreuse", (127, 255, 0)),
+    ("chocolate", (210, 105, 30)),
+    ("coral", (255, 127, 80)),
+    ("cornflowerblue", (100, 149, 237)),
+    ("cornsilk", (255, 248, 220)),
+    ("crimson", (220, 20, 60)),
+    ("cyan", (0, 255, 255)),
+    ("darkblue", (0, 0, 139)),
+    ("darkcyan", (0, 139, 139)),
+    ("darkgoldenrod", (184, 134, 11)),
+    ("darkgray", (169, 169, 169)),
+    ("darkgreen", (0, 100, 0)),
+    ("darkgrey", (169, 169, 169)),
+    ("darkkhaki", (189, 183, 107)),
+    ("darkmagenta", (139, 0, 139)),
+    ("darkolivegreen", (85, 107, 47)),
+    ("darkorange", (255, 140, 0)),
+    ("darkorchid", (153, 50, 204)),
+    ("darkred", (139, 0, 0)),
+    ("darksalmon", (233, 150, 122)),
+    ("darkseagreen", (143, 188, 143)),
+    ("darkslateblue", (72, 61, 139)),
+    ("darkslategray", (47, 79, 79)),
+    ("darkslategrey", (47, 79, 79)),
+    ("darkturquoise", (0, 206, 209)),
+    ("darkviolet", (148, 0, 211)),
+    ("deeppink", (255, 20, 147)),
+    ("deepskyblue", (0, 191, 255)),
+    ("dimgray", (105, 105, 105)),
+    ("dimgrey", (105, 105, 105)),
+    ("dodgerblue", (30, 144, 255)),
+    ("firebrick", (178, 34, 34)),
+    ("floralwhite", (255, 250, 240)),
+    ("forestgreen", (34, 139, 34)),
+    ("fuchsia", (255, 0, 255)),
+    ("gainsboro", (220, 220, 220)),
+    ("ghostwhite", (248, 248, 255)),
+    ("gold", (255, 215, 0)),
+    ("goldenrod", (218, 165, 32)),
+    ("gray", (128, 128, 128)),
+    ("green", (0, 128, 0)),
+    ("greenyellow", (173, 255, 47)),
+    ("grey", (128, 128, 128)),
+    ("honeydew", (240, 255, 240)),
+    ("hotpink", (255, 105, 180)),
+    ("indianred", (205, 92, 92)),
+    ("indigo", (75, 0, 130)),
+    ("ivory", (255, 255, 240)),
+    ("khaki", (240, 230, 140)),
+    ("lavender", (230, 230, 250)),
+    ("lavenderblush", (255, 240, 245)),
+    ("lawngreen", (124, 252, 0)),
+    ("lemonchiffon", (255, 250, 205)),
+    ("lightblue", (173, 216, 230)),
+    ("lightcoral", (240, 128, 128)),
+    ("lightcyan", (224, 255, 255)),
+    ("lightgoldenrod", (238, 221, 130)),
+    ("lightgoldenrodyellow", (250, 250, 210)),
+    ("lightgray", (211, 211, 211)),
+    ("lightgreen", (144, 238, 144)),
+    ("lightgrey", (211, 211, 211)),
+    ("lightpink", (255, 182, 193)),
+    ("lightsalmon", (255, 160, 122)),
+    ("lightseagreen", (32, 178, 170)),
+    ("lightskyblue", (135, 206, 250)),
+    ("lightslateblue", (132, 112, 255)),
+    ("lightslategray", (119, 136, 153)),
+    ("lightslategrey", (119, 136, 153)),
+    ("lightsteelblue", (176, 196, 222)),
+    ("lightyellow", (255, 255, 224)),
+    ("lime", (0, 255, 0)),
+    ("limegreen", (50, 205, 50)),
+    ("linen", (250, 240, 230)),
+    ("magenta", (255, 0, 255)),
+    ("maroon", (128, 0, 0)),
+    ("mediumaquamarine", (102, 205, 170)),
+    ("mediumblue", (0, 0, 205)),
+    ("mediumorchid", (186, 85, 211)),
+    ("mediumpurple", (147, 112, 219)),
+    ("mediumseagreen", (60, 179, 113)),
+    ("mediumslateblue", (123, 104, 238)),
+    ("mediumspringgreen", (0, 250, 154)),
+    ("mediumturquoise", (72, 209, 204)),
+    ("mediumvioletred", (199, 21, 133)),
+    ("midnightblue", (25, 25, 112)),
+    ("mintcream", (245, 255, 250)),
+    ("mistyrose", (255, 228, 225)),
+    ("moccasin", (255, 228, 181)),
+    ("navajowhite", (255, 222, 173)),
+    ("navy", (0, 0, 128)),
+    ("navyblue", (0, 0, 128)),
+    ("oldlace", (253, 245, 230)),
+    ("olive", (128, 128, 0)),
+    ("olivedrab", (107, 142, 35)),
+    ("orange", (255, 165, 0)),
+    ("orangered", (255, 69, 0)),
+    ("orchid", (218, 112, 214)),
+    ("palegoldenrod", (238, 232, 170)),
+    ("palegreen", (152, 251, 152)),
+    ("paleturquoise", (175, 238, 238)),
+    ("palevioletred", (219, 112, 147)),
+    ("papayawhip", (255, 239, 213)),
+    ("peachpuff", (255, 218, 185)),
+    ("peru", (205, 133, 63)),
+    ("pink", (255, 192, 203)),
+    ("plum", (221, 160, 221)),
+    ("powderblue", (176, 224, 230)),
+    ("purple", (128, 0, 128)),
+    ("rebeccapurple", (102, 51, 153)),
+    ("red", (255, 0, 0)),
+    ("rosybrown", (188, 143, 143)),
+    ("royalblue", (65, 105, 225)),
+    ("saddlebrown", (139, 69, 19)),
+    ("salmon", (250, 128, 114)),
+    ("sandybrown", (244, 164, 96)),
+    ("seagreen", (46, 139, 87)),
+    ("seashell", (255, 245, 238)),
+    ("sienna", (160, 82, 45)),
+    ("silver", (192, 192, 192)),
+    ("skyblue", (135, 206, 235)),
+    ("slateblue", (106, 90, 205)),
+    ("slategray", (112, 128, 144)),
+    ("slategrey", (112, 128, 144)),
+    ("snow", (255, 250, 250)),
+    ("springgreen", (0, 255, 127)),
+    ("steelblue", (70, 130, 180)),
+    ("tan", (210, 180, 140)),
+    ("teal", (0, 128, 128)),
+    ("thistle", (216, 191, 216)),
+    ("tomato", (255, 99, 71)),
+    ("turquoise", (64, 224, 208)),
+    ("violet", (238, 130, 238)),
+    ("violetred", (208, 32, 144)),
+    ("wheat", (245, 222, 179)),
+    ("white", (255, 255, 255)),
+    ("whitesmoke", (245, 245, 245)),
+    ("yellow", (255, 255, 0)),
+    ("yellowgreen", (154, 205, 50)),
+];
+
+/// Color for an RGB value, either as truecolor or as the nearest 256-color palette entry
+pub fn rgb_color((r, g, b): (u8, u8, u8), truecolor: bool) -> Color {
+    if truecolor {
+        Color::Rgb { r, g, b }
+    } else {
+        Color::AnsiValue(nearest_ansi_256((r, g, b)))
+    }
+}
+
+/// Quantize an RGB color to the 256-color palette unless truecolor is on
+///
+/// Palette and named colors pass through unchanged.
+pub fn fit_color(color: Color, truecolor: bool) -> Color {
+    match color {
+        Color::Rgb { r, g, b } => rgb_color((r, g, b), truecolor),
+        other => other,
+    }
+}
+
 /// Create a faded RGB color
-pub fn fade_color_rgb((r, g, b): (u8, u8, u8), alpha: f32) -> Color {
+pub fn fade_color_rgb(rgb: (u8, u8, u8), alpha: f32) -> Color {
+    fade_toward(rgb, (0, 0, 0), alpha)
+}
+
+/// Blend a color toward a background; `alpha` 1.0 is the full color and
+/// 0.0 the background
+pub fn fade_toward(rgb: (u8, u8, u8), background: (u8, u8, u8), alpha: f32) -> Color {
+    let mix = |c: u8, bg: u8| (bg as f32 + (c as f32 - bg as f32) * alpha).clamp(0.0, 255.0) as u8;
     Color::Rgb {
-        r: (r as f32 * alpha).clamp(0.0, 255.0) as u8,
-        g: (g as f32 * alpha).clamp(0.0, 255.0) as u8,
-        b: (b as f32 * alpha).clamp(0.0, 255.0) as u8,
+        r: mix(rgb.0, background.0),
+        g: mix(rgb.1, background.1),
+        b: mix(rgb.2, background.2),
+    }
+}
+
+/// Convert hue (0.0-1.0, wrapping), saturation and value (0.0-1.0) to RGB
+pub fn hsv_to_rgb(hue: f32, saturation: f32, value: f32) -> (u8, u8, u8) {
+    let h = hue.rem_euclid(1.0) * 6.0;
+    let s = saturation.clamp(0.0, 1.0);
+    let v = value.clamp(0.0, 1.0);
+    let c = v * s;
+    let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let m = v - c;
+    let to_u8 = |channel: f32| ((channel + m) * 255.0).round().clamp(0.0, 255.0) as u8;
+    (to_u8(r), to_u8(g), to_u8(b))
+}
+
+/// The color trails fade toward
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Background {
+    /// Ask the terminal for its background color at startup
+    Auto,
+    Color(Color),
+}
+
+impl Background {
+    /// Parse `auto` or any `--color` syntax
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        if spec.trim().eq_ignore_ascii_case("auto") {
+            Ok(Self::Auto)
+        } else {
+            parse_color(spec).map(Self::Color)
+        }
     }
 }
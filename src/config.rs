@@ -0,0 +1,459 @@
+use crossterm::style::Color;
+
+use crate::capability::ColorDepth;
+use crate::charset::Charset;
+use crate::colors::{Background, MatrixColorScheme};
+use crate::gradient::{Gradient, SpatialGradient};
+use crate::highlight::Highlighter;
+use crate::rainbow::Rainbow;
+use crate::matrix::{
+    MATRIX_CHARS_ALPHANUMERIC, MATRIX_CHARS_GREEK, MATRIX_CHARS_KATAKANA, MATRIX_CHARS_SYMBOLS,
+};
+use crate::scheduler::{MAX_FPS, MIN_FPS};
+use crate::source::SourceText;
+use crate::words::Wordlist;
+
+// ==== Limits ====
+pub const TRAIL_MIN_LIMIT: usize = 4;
+pub const TRAIL_MAX_LIMIT: usize = 40;
+pub const MIN_SPEED: f32 = 0.1;
+pub const MAX_SPEED: f32 = 10.0;
+
+/// Settings for a single Matrix rain instance
+///
+/// Built with `MatrixConfig::default()` followed by `with_*` calls. Values
+/// are clamped to their valid ranges as they are set, so every config is
+/// usable as-is.
+#[derive(Clone, Debug)]
+pub struct MatrixConfig {
+    initial_drops: usize,
+    min_trail: usize,
+    max_trail: usize,
+    speed: f32,
+    fps: u32,
+    glitch_probability: f32,
+    flicker_probability: f32,
+    stuck_probability: f32,
+    new_drop_probability: f32,
+    enable_stuck: bool,
+    rgb_fade: bool,
+    color_scheme: MatrixColorScheme,
+    gradient: Option<Gradient>,
+    head_color: Option<Color>,
+    sticky_color: Option<Color>,
+    glitch_color: Option<Color>,
+    bold_head: Option<usize>,
+    dim_tail: bool,
+    glow: bool,
+    mono: bool,
+    narrow: bool,
+    background: Option<Background>,
+    rainbow: Option<Rainbow>,
+    spatial: Option<SpatialGradient>,
+    charset: Charset,
+    words: Option<Wordlist>,
+    source: Option<SourceText>,
+    feed_only: bool,
+    highlighter: Option<Highlighter>,
+    color_depth: Option<ColorDepth>,
+    seed: Option<u64>,
+}
+
+impl Default for MatrixConfig {
+    fn default() -> Self {
+        Self {
+            initial_drops: 10,
+            min_trail: 8,
+            max_trail: 25,
+            speed: 1.0,
+            fps: 30,
+            glitch_probability: 0.003,
+            flicker_probability: 0.01,
+            stuck_probability: 0.02,
+            new_drop_probability: 0.05,
+            enable_stuck: true,
+            rgb_fade: false,
+            color_scheme: MatrixColorScheme::Green,
+            gradient: None,
+            head_color: None,
+            sticky_color: None,
+            glitch_color: None,
+            bold_head: None,
+            dim_tail: false,
+            glow: false,
+            mono: false,
+            narrow: false,
+            background: None,
+            rainbow: None,
+            spatial: None,
+            charset: Charset::new(classic_charset()),
+            words: None,
+            source: None,
+            feed_only: false,
+            highlighter: None,
+            color_depth: None,
+            seed: None,
+        }
+    }
+}
+
+impl MatrixConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // ==== Builder Methods ====
+
+    pub fn with_initial_drops(mut self, drops: usize) -> Self {
+        self.initial_drops = drops;
+        self
+    }
+
+    /// Set the minimum trail length (4-40, capped at the maximum when read)
+    pub fn with_min_trail(mut self, len: usize) -> Self {
+        self.min_trail = len.clamp(TRAIL_MIN_LIMIT, TRAIL_MAX_LIMIT);
+        self
+    }
+
+    /// Set the maximum trail length (4-40)
+    pub fn with_max_trail(mut self, len: usize) -> Self {
+        self.max_trail = len.clamp(TRAIL_MIN_LIMIT, TRAIL_MAX_LIMIT);
+        self
+    }
+
+    /// Set the rain speed multiplier (0.1-10.0)
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = speed.clamp(MIN_SPEED, MAX_SPEED);
+        self
+    }
+
+    /// Set the target frame rate used by the terminal runner (1-144)
+    pub fn with_fps(mut self, fps: u32) -> Self {
+        self.fps = fps.clamp(MIN_FPS, MAX_FPS);
+        self
+    }
+
+    pub fn with_glitch_probability(mut self, prob: f32) -> Self {
+        self.glitch_probability = prob.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_flicker_probability(mut self, prob: f32) -> Self {
+        self.flicker_probability = prob.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_stuck_probability(mut self, prob: f32) -> Self {
+        self.stuck_probability = prob.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_new_drop_probability(mut self, prob: f32) -> Self {
+        self.new_drop_probability = prob.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_stuck(mut self, enable: bool) -> Self {
+        self.enable_stuck = enable;
+        self
+    }
+
+    pub fn with_rgb_fade(mut self, enable: bool) -> Self {
+        self.rgb_fade = enable;
+        self
+    }
+
+    pub fn with_color_scheme(mut self, scheme: MatrixColorScheme) -> Self {
+        self.color_scheme = scheme;
+        self
+    }
+
+    /// Color trails with a gradient instead of the color scheme's steps
+    pub fn with_gradient(mut self, gradient: Option<Gradient>) -> Self {
+        self.gradient = gradient;
+        self
+    }
+
+    /// Override the color of the leading character of each drop
+    pub fn with_head_color(mut self, color: Option<Color>) -> Self {
+        self.head_color = color;
+        self
+    }
+
+    /// Override the color of stuck characters
+    pub fn with_sticky_color(mut self, color: Option<Color>) -> Self {
+        self.sticky_color = color;
+        self
+    }
+
+    /// Override the color of glitching characters
+    pub fn with_glitch_color(mut self, color: Option<Color>) -> Self {
+        self.glitch_color = color;
+        self
+    }
+
+    /// Draw the head and the `cells` after it in bold (`None` = no bold)
+    pub fn with_bold_head(mut self, cells: Option<usize>) -> Self {
+        self.bold_head = cells;
+        self
+    }
+
+    /// Draw the back half of each trail with the terminal's dim attribute
+    pub fn with_dim_tail(mut self, enable: bool) -> Self {
+        self.dim_tail = enable;
+        self
+    }
+
+    /// Briefly light the cell just ahead of each head as it moves into it
+    pub fn with_glow(mut self, enable: bool) -> Self {
+        self.glow = enable;
+        self
+    }
+
+    /// Draw without color, showing trail intensity through glyphs and
+    /// bold/normal/dim text
+    pub fn with_mono(mut self, enable: bool) -> Self {
+        self.mono = enable;
+        self
+    }
+
+    /// Replace glyphs that aren't reliably one cell wide with narrow
+    /// stand-ins, see `charset::narrow_fallback`
+    pub fn with_narrow(mut self, enable: bool) -> Self {
+        self.narrow = enable;
+        self
+    }
+
+    /// Fade trails toward this background instead of black
+    ///
+    /// `Background::Auto` is resolved by `run_matrix` by asking the terminal;
+    /// elsewhere it behaves like no background.
+    pub fn with_background(mut self, background: Option<Background>) -> Self {
+        self.background = background;
+        self
+    }
+
+    /// Cycle the base hue of drops instead of using the color scheme
+    ///
+    /// Takes precedence over a trail gradient; head, sticky and glitch
+    /// colors still apply.
+    pub fn with_rainbow(mut self, rainbow: Option<Rainbow>) -> Self {
+        self.rainbow = rainbow;
+        self
+    }
+
+    /// Vary the base color of drops by their position on screen
+    ///
+    /// Takes precedence over a trail gradient, but not over a rainbow.
+    pub fn with_spatial_gradient(mut self, spatial: Option<SpatialGradient>) -> Self {
+        self.spatial = spatial;
+        self
+    }
+
+    /// Set the characters drops are made of (ignored if empty)
+    pub fn with_charset(mut self, charset: impl Into<Charset>) -> Self {
+        let charset = charset.into();
+        if !charset.is_empty() {
+            self.charset = charset;
+        }
+        self
+    }
+
+    /// Have each drop spell a word from the list behind its head, with
+    /// charset characters filling the rest of the trail
+    pub fn with_words(mut self, words: Option<Wordlist>) -> Self {
+        self.words = words;
+        self
+    }
+
+    /// Fill each drop with a run of text from the source instead of charset
+    /// characters (takes precedence over words)
+    pub fn with_source(mut self, source: Option<SourceText>) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// Only spawn drops for text passed to `MatrixSimulation::feed`
+    ///
+    /// Drops that fall off screen are not replaced, so the rain drains away
+    /// when the input stops.
+    pub fn with_feed_only(mut self, enable: bool) -> Self {
+        self.feed_only = enable;
+        self
+    }
+
+    /// Color fed text by pattern, e.g. errors in red (`None` = rain colors)
+    pub fn with_highlighter(mut self, highlighter: Option<Highlighter>) -> Self {
+        self.highlighter = highlighter;
+        self
+    }
+
+    /// Force a terminal color depth instead of detecting it (`None` = detect)
+    pub fn with_color_depth(mut self, depth: Option<ColorDepth>) -> Self {
+        self.color_depth = depth;
+        self
+    }
+
+    /// Seed the simulation's random number generator for reproducible runs
+    pub fn with_seed(mut self, seed: Option<u64>) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    // ==== Getters ====
+
+    pub fn initial_drops(&self) -> usize {
+        self.initial_drops
+    }
+
+    /// Shortest trail, never longer than `max_trail` whatever order the
+    /// two were set in
+    pub fn min_trail(&self) -> usize {
+        self.min_trail.min(self.max_trail)
+    }
+
+    pub fn max_trail(&self) -> usize {
+        self.max_trail
+    }
+
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    pub fn fps(&self) -> u32 {
+        self.fps
+    }
+
+    pub fn glitch_probability(&self) -> f32 {
+        self.glitch_probability
+    }
+
+    pub fn flicker_probability(&self) -> f32 {
+        self.flicker_probability
+    }
+
+    pub fn stuck_probability(&self) -> f32 {
+        self.stuck_probability
+    }
+
+    pub fn new_drop_probability(&self) -> f32 {
+        self.new_drop_probability
+    }
+
+    pub fn stuck_enabled(&self) -> bool {
+        self.enable_stuck
+    }
+
+    pub fn rgb_fade(&self) -> bool {
+        self.rgb_fade
+    }
+
+    pub fn color_scheme(&self) -> MatrixColorScheme {
+        self.color_scheme
+    }
+
+    pub fn gradient(&self) -> Option<&Gradient> {
+        self.gradient.as_ref()
+    }
+
+    pub fn head_color(&self) -> Option<Color> {
+        self.head_color
+    }
+
+    pub fn sticky_color(&self) -> Option<Color> {
+        self.sticky_color
+    }
+
+    pub fn glitch_color(&self) -> Option<Color> {
+        self.glitch_color
+    }
+
+    pub fn bold_head(&self) -> Option<usize> {
+        self.bold_head
+    }
+
+    pub fn dim_tail(&self) -> bool {
+        self.dim_tail
+    }
+
+    pub fn glow(&self) -> bool {
+        self.glow
+    }
+
+    pub fn mono(&self) -> bool {
+        self.mono
+    }
+
+    pub fn narrow(&self) -> bool {
+        self.narrow
+    }
+
+    /// Terminal cells between neighbouring drops: 2 when the charset has
+    /// wide glyphs, so they don't overlap, otherwise 1
+    pub fn column_stride(&self) -> u16 {
+        let width = self
+            .charset
+            .width()
+            .max(self.words.as_ref().map_or(1, Wordlist::width))
+            .max(self.source.as_ref().map_or(1, SourceText::width));
+        if self.narrow || width < 2 { 1 } else { 2 }
+    }
+
+    pub fn background(&self) -> Option<Background> {
+        self.background
+    }
+
+    /// The resolved background color, if any
+    pub fn background_color(&self) -> Option<Color> {
+        match self.background {
+            Some(Background::Color(color)) => Some(color),
+            _ => None,
+        }
+    }
+
+    pub fn rainbow(&self) -> Option<Rainbow> {
+        self.rainbow
+    }
+
+    pub fn spatial_gradient(&self) -> Option<&SpatialGradient> {
+        self.spatial.as_ref()
+    }
+
+    pub fn charset(&self) -> &Charset {
+        &self.charset
+    }
+
+    pub fn words(&self) -> Option<&Wordlist> {
+        self.words.as_ref()
+    }
+
+    pub fn source(&self) -> Option<&SourceText> {
+        self.source.as_ref()
+    }
+
+    pub fn feed_only(&self) -> bool {
+        self.feed_only
+    }
+
+    pub fn highlighter(&self) -> Option<&Highlighter> {
+        self.highlighter.as_ref()
+    }
+
+    pub fn color_depth(&self) -> Option<ColorDepth> {
+        self.color_depth
+    }
+
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+}
+
+/// The original mix of the katakana, alphanumeric, symbols and greek sets
+pub fn classic_charset() -> Vec<char> {
+    let mut v = Vec::new();
+    v.extend_from_slice(MATRIX_CHARS_KATAKANA);
+    v.extend_from_slice(MATRIX_CHARS_ALPHANUMERIC);
+    v.extend_from_slice(MATRIX_CHARS_SYMBOLS);
+    v.extend_from_slice(MATRIX_CHARS_GREEK);
+    v
+}
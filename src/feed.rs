@@ -0,0 +1,148 @@
+use std::io::{BufRead, BufReader, Read};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+
+/// Size of the reads in `FeedMode::Stream`, in bytes
+const STREAM_CHUNK_SIZE: usize = 256;
+
+/// How incoming data is cut into drops
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FeedMode {
+    /// Each line becomes a drop (or several, if it is longer than a trail)
+    #[default]
+    Lines,
+    /// Data becomes drops as soon as it arrives, without waiting for a line
+    /// break; for programs that don't write whole lines
+    Stream,
+}
+
+impl FeedMode {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "lines" | "line" => Ok(Self::Lines),
+            "stream" | "bytes" => Ok(Self::Stream),
+            other => Err(format!("unknown input mode '{other}', expected lines or stream")),
+        }
+    }
+}
+
+/// Text arriving from outside the simulation, e.g. piped into stdin
+///
+/// The source is read on a background thread, so reading never holds up the
+/// animation. Pass everything `drain` returns to `MatrixSimulation::feed`.
+pub struct Feed {
+    rx: Receiver<String>,
+    closed: bool,
+    exit_on_close: bool,
+}
+
+impl Feed {
+    /// Read from any source on a background thread
+    pub fn from_reader(reader: impl Read + Send + 'static, mode: FeedMode) -> Self {
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let mut reader = BufReader::new(reader);
+            match mode {
+                FeedMode::Lines => {
+                    let mut line = Vec::new();
+                    while reader.read_until(b'\n', &mut line).is_ok_and(|n| n > 0) {
+                        if tx.send(String::from_utf8_lossy(&line).into_owned()).is_err() {
+                            break;
+                        }
+                        line.clear();
+                    }
+                }
+                FeedMode::Stream => {
+                    let mut chunk = [0u8; STREAM_CHUNK_SIZE];
+                    // Bytes of a character split across reads wait for the rest
+                    let mut carry = Vec::new();
+                    while let Ok(n) = reader.read(&mut chunk) {
+                        if n == 0 {
+                            break;
+                        }
+                        carry.extend_from_slice(&chunk[..n]);
+                        let text = take_utf8(&mut carry);
+                        if !text.is_empty() && tx.send(text).is_err() {
+                            return;
+                        }
+                    }
+                    if !carry.is_empty() {
+                        let _ = tx.send(String::from_utf8_lossy(&carry).into_owned());
+                    }
+                }
+            }
+        });
+        Self { rx, closed: false, exit_on_close: false }
+    }
+
+    /// Read the process's standard input
+    pub fn stdin(mode: FeedMode) -> Self {
+        Self::from_reader(std::io::stdin(), mode)
+    }
+
+    /// Have `run_matrix_with_feed` quit once the input has ended and the
+    /// last drops have fallen, instead of idling until the user quits
+    pub fn with_exit_on_close(mut self, enable: bool) -> Self {
+        self.exit_on_close = enable;
+        self
+    }
+
+    /// Everything that arrived since the last call, without blocking
+    pub fn drain(&mut self) -> Vec<String> {
+        let mut received = Vec::new();
+        loop {
+            match self.rx.try_recv() {
+                Ok(text) => received.push(text),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.closed = true;
+                    break;
+                }
+            }
+        }
+        received
+    }
+
+    /// Whether the input has ended and everything has been drained
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    pub fn exit_on_close(&self) -> bool {
+        self.exit_on_close
+    }
+}
+
+/// Decode as much of `bytes` as possible, leaving an incomplete character at
+/// the end in place; invalid bytes become U+FFFD
+fn take_utf8(bytes: &mut Vec<u8>) -> String {
+    let mut text = String::new();
+    let mut rest: &[u8] = bytes;
+    loop {
+        match std::str::from_utf8(rest) {
+            Ok(valid) => {
+                text.push_str(valid);
+                rest = &[];
+                break;
+            }
+            Err(err) => {
+                let (valid, after) = rest.split_at(err.valid_up_to());
+                text.push_str(std::str::from_utf8(valid).expect("checked above"));
+                match err.error_len() {
+                    Some(len) => {
+                        text.push(char::REPLACEMENT_CHARACTER);
+                        rest = &after[len..];
+                    }
+                    // The input ends partway through a character
+                    None => {
+                        rest = after;
+                        break;
+                    }
+                }
+            }
+        }
+    }
+    let consumed = bytes.len() - rest.len();
+    bytes.drain(..consumed);
+    text
+}
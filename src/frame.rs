@@ -0,0 +1,153 @@
+use crossterm::style::Color;
+
+use crate::buffer::{Cell, CellAttrs};
+use crate::charset::narrow_fallback;
+
+// ==== Monochrome Levels ====
+/// Cells at least this bright are drawn bold in monochrome mode
+const MONO_BOLD_INTENSITY: f32 = 0.75;
+/// Cells dimmer than this are drawn with the dim attribute
+const MONO_DIM_INTENSITY: f32 = 0.35;
+/// The faintest cells swap their glyph for a lighter one, from dimmest up
+const MONO_FAINT_GLYPHS: &[(f32, char)] = &[(0.1, '.'), (0.2, ':')];
+
+/// One cell of a composed simulation frame
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FrameCell {
+    pub glyph: char,
+    pub color: Color,
+    /// Brightness from 0.0 (empty) to 1.0 (drop head)
+    pub intensity: f32,
+    pub attrs: CellAttrs,
+}
+
+impl FrameCell {
+    pub const EMPTY: FrameCell = FrameCell {
+        glyph: ' ',
+        color: Color::Reset,
+        intensity: 0.0,
+        attrs: CellAttrs { bold: false, dim: false },
+    };
+
+    pub fn new(glyph: char, color: Color, intensity: f32) -> Self {
+        Self { glyph, color, intensity, attrs: CellAttrs::default() }
+    }
+
+    pub fn with_attrs(mut self, attrs: CellAttrs) -> Self {
+        self.attrs = attrs;
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.glyph == ' '
+    }
+
+    /// The same cell without color, showing its intensity through bold,
+    /// normal or dim text and lighter glyphs at the very end of a trail
+    pub fn monochrome(self) -> Self {
+        if self.is_empty() {
+            return self;
+        }
+        let glyph = MONO_FAINT_GLYPHS
+            .iter()
+            .find(|(below, _)| self.intensity < *below)
+            .map_or(self.glyph, |&(_, glyph)| glyph);
+        let attrs = CellAttrs {
+            bold: self.intensity >= MONO_BOLD_INTENSITY,
+            dim: self.intensity < MONO_DIM_INTENSITY,
+        };
+        Self { glyph, color: Color::Reset, intensity: self.intensity, attrs }
+    }
+}
+
+impl Default for FrameCell {
+    fn default() -> Self {
+        Self::EMPTY
+    }
+}
+
+impl From<FrameCell> for Cell {
+    fn from(cell: FrameCell) -> Self {
+        Cell { ch: cell.glyph, fg: cell.color, attrs: cell.attrs }
+    }
+}
+
+/// Grid of cells produced by each simulation tick, stored row by row
+#[derive(Clone, Debug)]
+pub struct Frame {
+    cols: u16,
+    rows: u16,
+    cells: Vec<FrameCell>,
+}
+
+impl Frame {
+    pub fn new(cols: u16, rows: u16) -> Self {
+        Self {
+            cols,
+            rows,
+            cells: vec![FrameCell::EMPTY; cols as usize * rows as usize],
+        }
+    }
+
+    pub fn cols(&self) -> u16 {
+        self.cols
+    }
+
+    pub fn rows(&self) -> u16 {
+        self.rows
+    }
+
+    /// Reset every cell to empty
+    pub fn clear(&mut self) {
+        self.cells.fill(FrameCell::EMPTY);
+    }
+
+    fn index(&self, x: u16, y: u16) -> Option<usize> {
+        if x < self.cols && y < self.rows {
+            Some(y as usize * self.cols as usize + x as usize)
+        } else {
+            None
+        }
+    }
+
+    pub fn get(&self, x: u16, y: u16) -> Option<&FrameCell> {
+        self.index(x, y).map(|i| &self.cells[i])
+    }
+
+    /// Write a cell, ignoring out-of-bounds positions
+    pub fn set(&mut self, x: u16, y: u16, cell: FrameCell) {
+        if let Some(i) = self.index(x, y) {
+            self.cells[i] = cell;
+        }
+    }
+
+    /// Drop all color from the frame, see `FrameCell::monochrome`
+    pub fn make_monochrome(&mut self) {
+        for cell in &mut self.cells {
+            *cell = cell.monochrome();
+        }
+    }
+
+    /// Swap every glyph that isn't reliably one cell wide for a narrow
+    /// stand-in, see `charset::narrow_fallback`
+    pub fn make_narrow(&mut self) {
+        for cell in &mut self.cells {
+            cell.glyph = narrow_fallback(cell.glyph);
+        }
+    }
+
+    /// All cells, row by row
+    pub fn cells(&self) -> &[FrameCell] {
+        &self.cells
+    }
+
+    /// Iterate over non-empty cells as (x, y, cell)
+    pub fn iter(&self) -> impl Iterator<Item = (u16, u16, &FrameCell)> {
+        let cols = self.cols.max(1) as usize;
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|(_, cell)| !cell.is_empty())
+            .map(move |(i, cell)| ((i % cols) as u16, (i / cols) as u16, cell))
+    }
+}
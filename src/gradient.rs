@@ -0,0 +1,289 @@
+use crate::colors::{color_to_rgb, parse_color};
+
+/// Curve applied between two neighbouring gradient stops
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Easing {
+    #[default]
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+    /// Hold each stop's color until the next stop
+    Step,
+}
+
+impl Easing {
+    pub const NAMES: &[&str] = &["linear", "ease-in", "ease-out", "ease-in-out", "step"];
+
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name.trim().to_ascii_lowercase().replace('_', "-").as_str() {
+            "linear" => Ok(Self::Linear),
+            "ease-in" => Ok(Self::EaseIn),
+            "ease-out" => Ok(Self::EaseOut),
+            "ease-in-out" => Ok(Self::EaseInOut),
+            "step" => Ok(Self::Step),
+            other => Err(format!(
+                "unknown easing '{other}', expected one of: {}",
+                Self::NAMES.join(", ")
+            )),
+        }
+    }
+
+    /// Map a 0.0-1.0 progress value through the curve
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Self::Linear => t,
+            Self::EaseIn => t * t,
+            Self::EaseOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Self::EaseInOut => t * t * (3.0 - 2.0 * t),
+            Self::Step => 0.0,
+        }
+    }
+}
+
+/// A color at a position (0.0-1.0) along a gradient
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GradientStop {
+    pub position: f32,
+    pub rgb: (u8, u8, u8),
+}
+
+/// Multi-stop color gradient
+///
+/// Trails sample it from the head (0.0) to the tail end (1.0).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Gradient {
+    stops: Vec<GradientStop>,
+    easing: Easing,
+}
+
+impl Gradient {
+    /// Build a gradient from stops, sorting them by position
+    pub fn new(mut stops: Vec<GradientStop>) -> Result<Self, String> {
+        if stops.is_empty() {
+            return Err("a gradient needs at least one color".to_string());
+        }
+        for stop in &mut stops {
+            stop.position = stop.position.clamp(0.0, 1.0);
+        }
+        stops.sort_by(|a, b| a.position.total_cmp(&b.position));
+        Ok(Self { stops, easing: Easing::Linear })
+    }
+
+    /// Parse a comma-separated list of colors, each optionally followed by
+    /// `@position` as a fraction (`0.3`) or percentage (`30%`)
+    ///
+    /// Stops without a position are spread evenly between their neighbours,
+    /// e.g. `white,#8f8,#0f0@0.5,#030`.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut colors = Vec::new();
+        let mut positions = Vec::new();
+
+        for token in split_top_level(spec) {
+            let token = token.trim();
+            if token.is_empty() {
+                return Err(format!("empty color in gradient '{spec}'"));
+            }
+            let (color, position) = match token.rsplit_once('@') {
+                Some((color, pos)) => (color, Some(parse_position(pos)?)),
+                None => (token, None),
+            };
+            let color = parse_color(color)?;
+            colors.push(color_to_rgb(color).unwrap_or((0, 0, 0)));
+            positions.push(position);
+        }
+
+        if colors.is_empty() {
+            return Err("a gradient needs at least one color".to_string());
+        }
+
+        let positions = fill_positions(&positions);
+        let stops = colors
+            .into_iter()
+            .zip(positions)
+            .map(|(rgb, position)| GradientStop { position, rgb })
+            .collect();
+        Self::new(stops)
+    }
+
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    pub fn stops(&self) -> &[GradientStop] {
+        &self.stops
+    }
+
+    pub fn easing(&self) -> Easing {
+        self.easing
+    }
+
+    /// Interpolated color at position `t` (0.0-1.0)
+    pub fn sample(&self, t: f32) -> (u8, u8, u8) {
+        let t = t.clamp(0.0, 1.0);
+        let first = self.stops[0];
+        if t <= first.position {
+            return first.rgb;
+        }
+
+        for pair in self.stops.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            if t <= b.position {
+                let span = b.position - a.position;
+                if span <= f32::EPSILON {
+                    return b.rgb;
+                }
+                let local = self.easing.apply((t - a.position) / span);
+                return lerp_rgb(a.rgb, b.rgb, local);
+            }
+        }
+
+        self.stops[self.stops.len() - 1].rgb
+    }
+}
+
+/// Which way a spatial gradient runs across the screen
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GradientDirection {
+    /// Left to right
+    #[default]
+    Horizontal,
+    /// Top to bottom
+    Vertical,
+    /// From the center out to the corners
+    Radial,
+}
+
+impl GradientDirection {
+    pub const NAMES: &[&str] = &["horizontal", "vertical", "radial"];
+
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "horizontal" | "h" => Ok(Self::Horizontal),
+            "vertical" | "v" => Ok(Self::Vertical),
+            "radial" | "r" => Ok(Self::Radial),
+            other => Err(format!(
+                "unknown gradient direction '{other}', expected one of: {}",
+                Self::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+/// A gradient laid across the screen that sets each cell's base color
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpatialGradient {
+    direction: GradientDirection,
+    gradient: Gradient,
+}
+
+impl SpatialGradient {
+    pub fn new(direction: GradientDirection, gradient: Gradient) -> Self {
+        Self { direction, gradient }
+    }
+
+    pub fn direction(&self) -> GradientDirection {
+        self.direction
+    }
+
+    pub fn gradient(&self) -> &Gradient {
+        &self.gradient
+    }
+
+    /// Position (0.0-1.0) of a cell along the gradient
+    pub fn position(&self, x: u16, y: u16, cols: u16, rows: u16) -> f32 {
+        // The first and last cells sit exactly on the ends of the gradient,
+        // and a lone cell sits in the middle
+        let along = |i: u16, n: u16| if n <= 1 { 0.5 } else { i as f32 / (n - 1) as f32 };
+        match self.direction {
+            GradientDirection::Horizontal => along(x, cols),
+            GradientDirection::Vertical => along(y, rows),
+            GradientDirection::Radial => {
+                let dx = along(x, cols) * 2.0 - 1.0;
+                let dy = along(y, rows) * 2.0 - 1.0;
+                ((dx * dx + dy * dy) / 2.0).sqrt()
+            }
+        }
+    }
+
+    /// Base color of a cell
+    pub fn sample(&self, x: u16, y: u16, cols: u16, rows: u16) -> (u8, u8, u8) {
+        self.gradient.sample(self.position(x, y, cols, rows))
+    }
+}
+
+/// Linear interpolation between two colors
+pub fn lerp_rgb(a: (u8, u8, u8), b: (u8, u8, u8), t: f32) -> (u8, u8, u8) {
+    let mix = |x: u8, y: u8| (x as f32 + (y as f32 - x as f32) * t).round().clamp(0.0, 255.0) as u8;
+    (mix(a.0, b.0), mix(a.1, b.1), mix(a.2, b.2))
+}
+
+/// Split on commas that aren't inside parentheses, so `rgb(0,255,0)` stays whole
+fn split_top_level(spec: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in spec.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(&spec[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&spec[start..]);
+    parts
+}
+
+fn parse_position(pos: &str) -> Result<f32, String> {
+    let pos = pos.trim();
+    let value = match pos.strip_suffix('%') {
+        Some(pct) => pct.trim().parse::<f32>().map(|p| p / 100.0),
+        None => pos.parse::<f32>(),
+    }
+    .map_err(|_| format!("invalid gradient position '{pos}'"))?;
+
+    if (0.0..=1.0).contains(&value) {
+        Ok(value)
+    } else {
+        Err(format!("gradient position '{pos}' must be between 0 and 1 (or 0% and 100%)"))
+    }
+}
+
+/// Fill in missing stop positions the way CSS does: the ends default to 0
+/// and 1, and runs of unpositioned stops are spaced evenly
+fn fill_positions(positions: &[Option<f32>]) -> Vec<f32> {
+    let n = positions.len();
+    let mut filled: Vec<Option<f32>> = positions.to_vec();
+    if n == 1 {
+        return vec![filled[0].unwrap_or(0.0)];
+    }
+    if filled[0].is_none() {
+        filled[0] = Some(0.0);
+    }
+    if filled[n - 1].is_none() {
+        filled[n - 1] = Some(1.0);
+    }
+
+    let mut i = 0;
+    while i < n {
+        if filled[i].is_some() {
+            i += 1;
+            continue;
+        }
+        let start = i - 1;
+        let end = (i..n).find(|&j| filled[j].is_some()).unwrap_or(n - 1);
+        let (from, to) = (filled[start].unwrap(), filled[end].unwrap());
+        for (k, slot) in filled.iter_mut().enumerate().take(end).skip(i) {
+            *slot = Some(from + (to - from) * (k - start) as f32 / (end - start) as f32);
+        }
+        i = end;
+    }
+
+    filled.into_iter().map(|p| p.unwrap_or(0.0)).collect()
+}
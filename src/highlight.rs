@@ -0,0 +1,79 @@
+use regex::Regex;
+
+use crate::colors::parse_rgb;
+
+/// Color of lines with errors under `Highlighter::log_levels`
+pub const ERROR_COLOR: (u8, u8, u8) = (255, 60, 60);
+/// Color of lines with warnings under `Highlighter::log_levels`
+pub const WARN_COLOR: (u8, u8, u8) = (255, 176, 0);
+
+/// A pattern and the color of the lines it matches
+#[derive(Clone, Debug)]
+pub struct HighlightRule {
+    pattern: Regex,
+    color: (u8, u8, u8),
+}
+
+impl HighlightRule {
+    pub fn new(pattern: &str, color: (u8, u8, u8)) -> Result<Self, String> {
+        let pattern = Regex::new(pattern).map_err(|err| format!("invalid pattern '{pattern}': {err}"))?;
+        Ok(Self { pattern, color })
+    }
+
+    /// Parse `PATTERN=COLOR`, e.g. `ERROR=red` or `(?i)timeout|refused=#ff8800`
+    ///
+    /// The color follows the last `=`, so patterns may contain `=` themselves.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let (pattern, color) = spec
+            .rsplit_once('=')
+            .ok_or_else(|| format!("invalid rule '{spec}', expected PATTERN=COLOR"))?;
+        if pattern.is_empty() {
+            return Err(format!("invalid rule '{spec}', the pattern is empty"));
+        }
+        Self::new(pattern, parse_rgb(color)?)
+    }
+
+    pub fn pattern(&self) -> &str {
+        self.pattern.as_str()
+    }
+
+    pub fn color(&self) -> (u8, u8, u8) {
+        self.color
+    }
+
+    pub fn is_match(&self, text: &str) -> bool {
+        self.pattern.is_match(text)
+    }
+}
+
+/// Colors fed lines by the first rule they match
+///
+/// Lines matching no rule fall in the usual rain colors.
+#[derive(Clone, Debug, Default)]
+pub struct Highlighter {
+    rules: Vec<HighlightRule>,
+}
+
+impl Highlighter {
+    pub fn new(rules: Vec<HighlightRule>) -> Self {
+        Self { rules }
+    }
+
+    /// Errors in red and warnings in amber, matching common log level names
+    pub fn log_levels() -> Self {
+        Self::new(vec![
+            HighlightRule::new(r"(?i)\b(error|err|fatal|crit(ical)?|panic|emerg|alert)\b", ERROR_COLOR)
+                .expect("valid built-in pattern"),
+            HighlightRule::new(r"(?i)\b(warn(ing)?)\b", WARN_COLOR).expect("valid built-in pattern"),
+        ])
+    }
+
+    /// Color of the first rule `text` matches
+    pub fn color(&self, text: &str) -> Option<(u8, u8, u8)> {
+        self.rules.iter().find(|rule| rule.is_match(text)).map(HighlightRule::color)
+    }
+
+    pub fn rules(&self) -> &[HighlightRule] {
+        &self.rules
+    }
+}
@@ -0,0 +1,48 @@
+//! Make-it-Rain: a configurable Matrix rain effect
+//!
+//! Build a [`MatrixConfig`], hand it to a [`MatrixSimulation`], then step and
+//! render the simulation into any [`Renderer`]. [`run_matrix`] does all of
+//! this for the current terminal.
+
+pub mod buffer;
+pub mod capability;
+pub mod charset;
+pub mod colors;
+pub mod config;
+pub mod feed;
+pub mod frame;
+pub mod gradient;
+pub mod highlight;
+pub mod matrix;
+pub mod palettes;
+pub mod rainbow;
+pub mod renderer;
+pub mod scheduler;
+pub mod simulation;
+pub mod source;
+pub mod tail;
+pub mod themes;
+pub mod words;
+#[cfg(feature = "ratatui")]
+pub mod widget;
+
+pub use buffer::{Cell, CellAttrs};
+pub use capability::{ColorDepth, ColorDepthChoice};
+pub use charset::Charset;
+pub use colors::MatrixColorScheme;
+pub use config::MatrixConfig;
+pub use feed::{Feed, FeedMode};
+pub use frame::{Frame, FrameCell};
+pub use gradient::{Easing, Gradient, GradientDirection, SpatialGradient};
+pub use highlight::{HighlightRule, Highlighter};
+pub use matrix::{run_matrix, run_matrix_with_feed, RunStats};
+pub use palettes::{Palette, PaletteRegistry};
+pub use rainbow::{Rainbow, RainbowMode};
+pub use renderer::{CrosstermRenderer, MemoryRenderer, Renderer};
+pub use simulation::{MatrixRng, MatrixSimulation};
+pub use source::{SourceLoader, SourceText, Whitespace};
+pub use tail::TailReader;
+pub use themes::Theme;
+pub use words::Wordlist;
+#[cfg(feature = "ratatui")]
+pub use widget::{MatrixRainState, MatrixRainWidget};
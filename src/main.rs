@@ -1,7 +1,20 @@
-mod matrix;
-mod colors;
+use std::io::IsTerminal;
+use std::path::PathBuf;
 
-use clap::Parser;
+use clap::{error::ErrorKind, CommandFactory, Parser};
+use crossterm::style::{Color, Stylize};
+use make_it_rain::{
+    colors::{parse_color, Background},
+    charset, matrix, palettes, themes, Charset, ColorDepthChoice, Easing, Gradient, GradientDirection,
+    MatrixColorScheme, MatrixConfig, PaletteRegistry, Rainbow, RainbowMode, SpatialGradient, Theme,
+    Feed, FeedMode, HighlightRule, Highlighter, SourceLoader, TailReader, Whitespace, Wordlist,
+};
+
+/// Width of the character preview in --list-palettes, in terminal cells
+const PREVIEW_CELLS: usize = 24;
+
+/// Colors used by --spatial when --spatial-colors is not given
+const DEFAULT_SPATIAL_COLORS: &str = "lime,cyan";
 
 #[derive(Parser, Debug)]
 #[command(
@@ -20,13 +33,96 @@ struct Cli {
     #[arg(long, help = "Enable RGB fade coloring")]
     rgb: bool,
 
+    #[arg(long, help = "Draw without color, using bold and dim text (also set by NO_COLOR)")]
+    mono: bool,
+
     #[arg(
         short = 'c',
         long = "color",
-        default_value_t = 10,
-        help = "Terminal color code (0-15)"
+        default_value = "10",
+        value_parser = MatrixColorScheme::parse,
+        help = "Color: ANSI code (0-255), hex (#00ff41), rgb(r,g,b) or a CSS color name"
+    )]
+    color: MatrixColorScheme,
+
+    #[arg(
+        long,
+        value_parser = Gradient::parse,
+        help = "Trail gradient from head to tail, e.g. white,#8f8,#0f0@40%,#030"
+    )]
+    gradient: Option<Gradient>,
+
+    #[arg(
+        long,
+        default_value = "linear",
+        value_parser = Easing::parse,
+        help = "Gradient easing: linear, ease-in, ease-out, ease-in-out or step"
+    )]
+    gradient_easing: Easing,
+
+    #[arg(
+        long,
+        value_parser = themes::parse_theme,
+        help = "Named color theme (see --list-themes)"
+    )]
+    theme: Option<&'static Theme>,
+
+    #[arg(long, help = "List the built-in color themes and exit")]
+    list_themes: bool,
+
+    #[arg(long, value_parser = parse_color, help = "Color of the leading character of each drop")]
+    head_color: Option<Color>,
+
+    #[arg(long, value_name = "N", help = "Draw the head and the next N cells bold")]
+    bold_head: Option<usize>,
+
+    #[arg(long, help = "Draw the back half of each trail dimmed")]
+    dim_tail: bool,
+
+    #[arg(long, help = "Briefly light the cell ahead of each head")]
+    glow: bool,
+
+    #[arg(
+        long,
+        value_parser = GradientDirection::parse,
+        help = "Vary the color across the screen: horizontal, vertical or radial"
+    )]
+    spatial: Option<GradientDirection>,
+
+    #[arg(
+        long,
+        value_parser = Gradient::parse,
+        help = "Colors for --spatial, in --gradient syntax [default: lime,cyan]"
+    )]
+    spatial_colors: Option<Gradient>,
+
+    #[arg(
+        long,
+        value_parser = RainbowMode::parse,
+        help = "Cycle the hue over time, across columns or per drop: time, column or drop"
+    )]
+    rainbow: Option<RainbowMode>,
+
+    #[arg(long, default_value_t = 0.1, help = "Rainbow hue cycles per second")]
+    rainbow_speed: f32,
+
+    #[arg(long, default_value_t = 1.0, help = "Rainbow color saturation (0.0-1.0)")]
+    rainbow_saturation: f32,
+
+    #[arg(
+        long,
+        value_parser = Background::parse,
+        help = "Terminal background color trails fade toward, or 'auto' to ask the terminal"
+    )]
+    background: Option<Background>,
+
+    #[arg(
+        long,
+        default_value = "auto",
+        value_parser = ColorDepthChoice::parse,
+        help = "Terminal color depth: auto, truecolor, 256, 16 or mono"
     )]
-    color: u8,
+    color_depth: ColorDepthChoice,
 
     #[arg(long, default_value_t = 8, help = "Minimum trail length")]
     min_trail: usize,
@@ -46,12 +142,108 @@ struct Cli {
     #[arg(long, default_value_t = 0.05, help = "New drop probability")]
     drop_prob: f32,
 
-    #[arg(long, default_value_t = 12, help = "Frames per second")]
+    #[arg(long, default_value_t = 30, help = "Frames per second (1-144)")]
     fps: u32,
 
-    #[arg(long, default_value = "classic", help = "Character palette")]
+    #[arg(long, default_value_t = 1.0, help = "Rain speed multiplier")]
+    speed: f32,
+
+    #[arg(
+        long,
+        default_value = "classic",
+        help = "Character palette (see --list-palettes); combine with + (katakana+greek) or weight with : (katakana:3,digits:1)"
+    )]
     palette: String,
 
+    #[arg(long, help = "List the available character palettes and exit")]
+    list_palettes: bool,
+
+    #[arg(
+        long,
+        value_parser = Charset::from_chars,
+        help = "Use exactly these characters instead of a palette, e.g. \"01\""
+    )]
+    chars: Option<Charset>,
+
+    #[arg(
+        long,
+        value_parser = Wordlist::parse,
+        help = "Comma-separated words or phrases for drops to spell, e.g. \"neo,trinity\""
+    )]
+    words: Option<Wordlist>,
+
+    #[arg(long, value_name = "FILE", help = "File of words or phrases for drops to spell, one per line")]
+    wordlist: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with_all = ["words", "wordlist"],
+        help = "Stream text from these files or directories into the drops (repeatable)"
+    )]
+    source: Vec<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "PATTERN",
+        requires = "source",
+        help = "Only use source files matching this glob, e.g. \"*.rs\" (repeatable)"
+    )]
+    source_glob: Vec<String>,
+
+    #[arg(
+        long,
+        default_value = "collapse",
+        value_parser = Whitespace::parse,
+        help = "Whitespace in source text: keep, collapse or strip"
+    )]
+    source_whitespace: Whitespace,
+
+    #[arg(
+        long,
+        group = "feed",
+        conflicts_with_all = ["words", "wordlist", "source"],
+        help = "Rain the text piped into stdin, e.g. `tail -f app.log | mir --stdin`"
+    )]
+    stdin: bool,
+
+    #[arg(
+        long,
+        default_value = "lines",
+        value_parser = FeedMode::parse,
+        requires = "stdin",
+        help = "How piped text becomes drops: lines, or stream for output without line breaks"
+    )]
+    stdin_mode: FeedMode,
+
+    #[arg(long, requires = "stdin", help = "Quit once stdin ends and the last drops have fallen")]
+    exit_on_eof: bool,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        group = "feed",
+        conflicts_with_all = ["words", "wordlist", "source", "stdin"],
+        help = "Follow a log file and rain each new line, surviving rotation and truncation"
+    )]
+    tail: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "PATTERN=COLOR",
+        value_parser = HighlightRule::parse,
+        requires = "feed",
+        help = "Color lines matching a regex, e.g. \"timeout=orange\" (repeatable, first match wins; \
+                replaces the default red errors and amber warnings)"
+    )]
+    color_rule: Vec<HighlightRule>,
+
+    #[arg(long, help = "Replace wide and ambiguous-width glyphs with narrow ASCII stand-ins")]
+    narrow: bool,
+
+    #[arg(long, help = "Random seed for a reproducible animation")]
+    seed: Option<u64>,
+
     #[arg(long = "no-stuck", help = "Disable stuck characters")]
     no_stuck: bool,
 
@@ -62,85 +254,228 @@ struct Cli {
     no_flicker: bool,
 }
 
-fn get_charset_by_name(name: &str) -> &'static [char] {
-    match name.to_lowercase().as_str() {
-        "katakana" => matrix::MATRIX_CHARS_KATAKANA,
-        "alphanumeric" => matrix::MATRIX_CHARS_ALPHANUMERIC,
-        "symbols" => matrix::MATRIX_CHARS_SYMBOLS,
-        "greek" => matrix::MATRIX_CHARS_GREEK,
-        "classic" | _ => {
-            // For "classic" or any unrecognized name, return all combined
-            // We need to use a static reference, so we'll use a lazy_static or similar approach
-            // For now, let's default to katakana + alphanumeric which are most common
-            matrix::MATRIX_CHARS_KATAKANA
+/// Built-in palettes plus the user's own, warning about files that fail to load
+fn load_palettes() -> PaletteRegistry {
+    let mut registry = PaletteRegistry::new();
+    if let Some(dir) = palettes::user_palette_dir() {
+        for error in registry.load_dir(&dir) {
+            eprintln!("Warning: skipping palette {error}");
+        }
+    }
+    registry
+}
+
+/// Whether the user asked for no color through the NO_COLOR convention
+fn no_color() -> bool {
+    std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty())
+}
+
+/// Print every theme with a color swatch of its trail
+fn list_themes() {
+    let swatches = std::io::stdout().is_terminal() && !no_color();
+    for theme in themes::THEMES {
+        if swatches {
+            let gradient = theme.gradient();
+            let (r, g, b) = theme.head;
+            let mut swatch = "█".with(Color::Rgb { r, g, b }).to_string();
+            for i in 0..15 {
+                let (r, g, b) = gradient.sample(i as f32 / 14.0);
+                swatch.push_str(&"█".with(Color::Rgb { r, g, b }).to_string());
+            }
+            println!("{:<16} {}  {}", theme.name, swatch, theme.description);
+        } else {
+            println!("{:<16} {}", theme.name, theme.description);
         }
     }
 }
 
+/// Print every palette with a preview of its characters
+fn list_palettes(registry: &PaletteRegistry) {
+    for palette in registry.iter() {
+        // Sample evenly so mixed palettes show all of their sets, and pad by
+        // display width so wide palettes line up with narrow ones
+        let count = PREVIEW_CELLS / palette.width();
+        let step = palette.chars.len().div_ceil(count).max(1);
+        let preview: String = palette.chars.iter().step_by(step).take(count).collect();
+        let cells: usize = preview.chars().map(charset::char_width).sum();
+        let padding = " ".repeat(PREVIEW_CELLS.saturating_sub(cells));
+        let origin = if palette.user { " (user)" } else { "" };
+        println!("{:<16} {preview}{padding}  {}{}", palette.name, palette.description, origin);
+    }
+    if let Some(dir) = palettes::user_palette_dir() {
+        println!("\nUser palettes are read from {}", dir.display());
+    }
+}
+
 fn main() -> std::io::Result<()> {
     let cli = Cli::parse();
+
+    if cli.list_themes {
+        list_themes();
+        return Ok(());
+    }
+
+    let registry = load_palettes();
+    if cli.list_palettes {
+        list_palettes(&registry);
+        return Ok(());
+    }
     
     if cli.debug {
         eprintln!("Debug mode enabled");
-        eprintln!("Color: {}", cli.color);
+        eprintln!("Color: {:?}", cli.color);
+        eprintln!("Theme: {}", cli.theme.map_or("none", |theme| theme.name));
         eprintln!("Palette: {}", cli.palette);
         eprintln!("RGB mode: {}", cli.rgb);
+        eprintln!("Mono mode: {}", cli.mono || no_color());
+        eprintln!("FPS: {}", cli.fps);
         eprintln!("For detailed color reference, see: man mir");
     }
 
-    // Validate color range
-    let color_code = if cli.color > 15 {
-        eprintln!("Warning: Color code {} is out of range (0-15), using 10 (Green)", cli.color);
-        eprintln!("See 'man mir' for all available colors.");
-        10
-    } else {
-        cli.color
-    };
-
-    // Set up color scheme
-    let color_scheme = matrix::MatrixColorScheme::from_ansi_code(color_code);
-
     // Set glitch and flicker probabilities based on CLI flags and values
     let glitch_prob = if cli.no_glitch { 0.0 } else { cli.glitch_prob as f32 };
     let flicker_prob = if cli.no_flicker { 0.0 } else { cli.flicker_prob as f32 };
-    
-    matrix::set_glitch_probability(glitch_prob);
-    matrix::set_flicker_probability(flicker_prob);
-    matrix::set_min_trail(cli.min_trail);
-    matrix::set_max_trail(cli.max_trail);
-    matrix::set_new_drop_probability(cli.drop_prob);
-    matrix::set_framerate(cli.fps as f32);
-    matrix::set_stuck_probability(cli.stuck_prob);
-
-    // Handle charset selection
-    let charset: &[char] = if cli.palette.to_lowercase() == "classic" {
-        // For classic, create a combined charset
-        // Since we need a static reference, we'll use a different approach
-        // Let's create a Vec and leak it to get a 'static reference
-        let combined_charset: Vec<char> = {
-            let mut v = Vec::new();
-            v.extend_from_slice(matrix::MATRIX_CHARS_KATAKANA);
-            v.extend_from_slice(matrix::MATRIX_CHARS_ALPHANUMERIC);
-            v.extend_from_slice(matrix::MATRIX_CHARS_SYMBOLS);
-            v.extend_from_slice(matrix::MATRIX_CHARS_GREEK);
-            v
-        };
-        Box::leak(combined_charset.into_boxed_slice())
-    } else {
-        get_charset_by_name(&cli.palette)
+
+    // Handle charset selection; literal --chars win over --palette
+    let charset = match cli.chars {
+        Some(chars) => chars,
+        None => registry.parse(&cli.palette).unwrap_or_else(|err| {
+            Cli::command()
+                .error(ErrorKind::InvalidValue, format!("invalid value for '--palette': {err}"))
+                .exit()
+        }),
     };
 
+    // Words from --words and --wordlist are pooled
+    let words = match &cli.wordlist {
+        Some(path) => {
+            let file = Wordlist::from_file(path).unwrap_or_else(|err| {
+                Cli::command()
+                    .error(ErrorKind::InvalidValue, format!("invalid value for '--wordlist': {err}"))
+                    .exit()
+            });
+            Some(match cli.words {
+                Some(words) => words.merge(file),
+                None => file,
+            })
+        }
+        None => cli.words,
+    };
+
+    if cli.stdin && std::io::stdin().is_terminal() {
+        Cli::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "'--stdin' needs input piped in, e.g. `tail -f app.log | mir --stdin`",
+            )
+            .exit()
+    }
+
+    let feed = match &cli.tail {
+        Some(path) => {
+            let reader = TailReader::open(path).unwrap_or_else(|err| {
+                let message = format!("invalid value for '--tail': {}: {err}", path.display());
+                Cli::command().error(ErrorKind::InvalidValue, message).exit()
+            });
+            Some(Feed::from_reader(reader, FeedMode::Lines))
+        }
+        None => cli.stdin.then(|| Feed::stdin(cli.stdin_mode).with_exit_on_close(cli.exit_on_eof)),
+    };
+
+    // Fed lines show errors and warnings unless the user brings their own rules
+    let highlighter = feed.is_some().then(|| {
+        if cli.color_rule.is_empty() {
+            Highlighter::log_levels()
+        } else {
+            Highlighter::new(cli.color_rule.clone())
+        }
+    });
+
+    let source = (!cli.source.is_empty()).then(|| {
+        SourceLoader::new()
+            .with_globs(cli.source_glob.clone())
+            .with_whitespace(cli.source_whitespace)
+            .load(&cli.source)
+            .unwrap_or_else(|err| {
+                Cli::command()
+                    .error(ErrorKind::InvalidValue, format!("invalid value for '--source': {err}"))
+                    .exit()
+            })
+    });
+
     if cli.debug {
+        eprintln!("Source files: {}", source.as_ref().map_or(0, |source| source.files()));
+        eprintln!("Words: {}", words.as_ref().map_or(0, |words| words.words().len()));
+        eprintln!("Color rules: {}", highlighter.as_ref().map_or(0, |highlighter| highlighter.rules().len()));
         eprintln!("Selected charset size: {}", charset.len());
+        eprintln!("Widest glyph: {} cells", charset.width());
     }
 
+    let mut config = MatrixConfig::new()
+        .with_initial_drops(cli.drops)
+        .with_rgb_fade(cli.rgb)
+        .with_color_scheme(cli.color);
+
+    // A theme sets every color; an explicit --gradient or --head-color still wins
+    if let Some(theme) = cli.theme {
+        config = theme.apply(config);
+    }
+    if let Some(gradient) = cli.gradient {
+        config = config.with_gradient(Some(gradient.with_easing(cli.gradient_easing)));
+    }
+    if cli.head_color.is_some() {
+        config = config.with_head_color(cli.head_color);
+    }
+
+    // Either spatial option turns the mode on, filling in the other's default
+    let spatial = (cli.spatial.is_some() || cli.spatial_colors.is_some()).then(|| {
+        let colors = cli
+            .spatial_colors
+            .unwrap_or_else(|| Gradient::parse(DEFAULT_SPATIAL_COLORS).expect("valid default"));
+        SpatialGradient::new(cli.spatial.unwrap_or_default(), colors)
+    });
+
+    let config = config
+        .with_min_trail(cli.min_trail)
+        .with_max_trail(cli.max_trail)
+        .with_glitch_probability(glitch_prob)
+        .with_flicker_probability(flicker_prob)
+        .with_stuck_probability(cli.stuck_prob)
+        .with_new_drop_probability(cli.drop_prob)
+        .with_speed(cli.speed)
+        .with_fps(cli.fps)
+        .with_stuck(!cli.no_stuck)
+        .with_charset(charset)
+        .with_words(words)
+        .with_source(source)
+        .with_color_depth(cli.color_depth.depth())
+        .with_bold_head(cli.bold_head)
+        .with_dim_tail(cli.dim_tail)
+        .with_glow(cli.glow)
+        .with_mono(cli.mono || no_color())
+        .with_narrow(cli.narrow)
+        .with_feed_only(feed.is_some())
+        .with_highlighter(highlighter)
+        .with_background(cli.background)
+        .with_spatial_gradient(spatial)
+        .with_rainbow(cli.rainbow.map(|mode| {
+            Rainbow::new(mode)
+                .with_speed(cli.rainbow_speed)
+                .with_saturation(cli.rainbow_saturation)
+        }))
+        .with_seed(cli.seed);
+
     // Run the matrix effect
-    matrix::run_matrix(
-        cli.drops,
-        cli.rgb,
-        charset,
-        cli.fps,
-        !cli.no_stuck,
-        color_scheme,
-    )
+    let stats = matrix::run_matrix_with_feed(config, feed)?;
+    if cli.debug {
+        eprintln!("Seed: {}", stats.seed);
+        eprintln!("Color depth: {:?}", stats.color_depth);
+        eprintln!("Background: {:?}", stats.background);
+        eprintln!(
+            "Frames rendered: {}, dropped: {} (target {:.0} fps, average {:.1} fps)",
+            stats.frames, stats.dropped_frames, stats.target_fps, stats.average_fps,
+        );
+    }
+    println!("Goodbye from the Matrix...");
+    Ok(())
 }
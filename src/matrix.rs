@@ -1,28 +1,32 @@
 use crossterm::{
-    cursor::{Hide, MoveTo, Show},
+    cursor::{Hide, Show},
     event::{poll, read, Event, KeyCode, KeyModifiers},
-    execute, queue,
-    style::{Color, Print, SetForegroundColor},
+    execute,
+    style::{Color, SetForegroundColor},
     terminal::{
         disable_raw_mode, enable_raw_mode, size, Clear, ClearType, EnterAlternateScreen,
         LeaveAlternateScreen,
     },
 };
-use ctrlc;
-use rand::{rng, RngExt};
-use rand::prelude::{SliceRandom, IndexedRandom};
+use rand::{Rng, RngExt};
+use rand::prelude::IndexedRandom;
 use std::{
-    collections::HashMap,
-    io::{stdout, Write},
-    sync::{
-        atomic::{AtomicU32, AtomicBool, Ordering},
-        Arc,
-    },
-    thread::sleep,
-    time::{Duration, Instant},
+    io::stdout,
+    ops::RangeInclusive,
+    time::Duration,
 };
 
-pub use crate::colors::{MatrixColorScheme, fade_color_rgb};
+pub use crate::colors::{MatrixColorScheme, fade_color_rgb, fade_toward, fit_color, rgb_color};
+use crate::buffer::CellAttrs;
+use crate::capability::{query_background, ColorDepth};
+use crate::colors::{color_to_rgb, Background};
+use crate::config::MatrixConfig;
+use crate::feed::Feed;
+use crate::frame::{Frame, FrameCell};
+use crate::rainbow::RainbowMode;
+use crate::renderer::{CrosstermRenderer, Renderer};
+use crate::scheduler::FrameScheduler;
+use crate::simulation::MatrixSimulation;
 
 // ==== Visual Character Sets ====
 pub const MATRIX_CHARS_KATAKANA: &[char] = &[
@@ -41,80 +45,194 @@ pub const MATRIX_CHARS_SYMBOLS: &[char] = &[
     ':','·','¦','‡','†','°','¤','═','║','╔','╗','╚','╝','╠','╣','╦','╩','╬',
 ];
 
+pub const MATRIX_CHARS_DIGITS: &[char] = &['0','1','2','3','4','5','6','7','8','9'];
+
 pub const MATRIX_CHARS_GREEK: &[char] = &[
     'Α','Β','Γ','Δ','Ε','Ζ','Η','Θ','Ι','Κ','Λ','Μ','Ν','Ξ','Ο','Π','Ρ','Σ','Τ','Υ','Φ','Χ','Ψ','Ω',
 ];
 
+pub const MATRIX_CHARS_BINARY: &[char] = &['0','1'];
+
+pub const MATRIX_CHARS_HEX: &[char] = &[
+    '0','1','2','3','4','5','6','7','8','9','A','B','C','D','E','F',
+];
+
+/// Braille patterns, without the blank U+2800
+pub const MATRIX_CHARS_BRAILLE: RangeInclusive<char> = '\u{2801}'..='\u{28FF}';
+
+pub const MATRIX_CHARS_BOX_DRAWING: RangeInclusive<char> = '\u{2500}'..='\u{257F}';
+
+/// Elder Futhark, Anglo-Saxon and Younger Futhark runes
+pub const MATRIX_CHARS_RUNIC: RangeInclusive<char> = '\u{16A0}'..='\u{16EA}';
+
+pub const MATRIX_CHARS_CYRILLIC: &[char] = &[
+    'А','Б','В','Г','Д','Е','Ж','З','И','Й','К','Л','М','Н','О','П','Р','С','Т','У','Ф','Х',
+    'Ц','Ч','Ш','Щ','Ъ','Ы','Ь','Э','Ю','Я',
+];
+
+pub const MATRIX_CHARS_HANGUL: &[char] = &[
+    '가','나','다','라','마','바','사','아','자','차','카','타','파','하','거','너','더','러','머','버',
+    '서','어','저','처','커','터','퍼','허','고','노','도','로','모','보','소','오','조','초','코','토',
+    '포','호',
+];
+
+/// Devanagari vowels and consonants; combining vowel signs are left out as
+/// they can't stand in a cell on their own
+pub const MATRIX_CHARS_DEVANAGARI: &[char] = &[
+    'अ','आ','इ','ई','उ','ऊ','ए','ऐ','ओ','औ','क','ख','ग','घ','ङ','च','छ','ज','झ','ञ',
+    'ट','ठ','ड','ढ','ण','त','थ','द','ध','न','प','फ','ब','भ','म','य','र','ल','व','श',
+    'ष','स','ह',
+];
+
+pub const MATRIX_CHARS_MATH: &[char] = &[
+    '∀','∂','∃','∄','∅','∆','∇','∈','∉','∋','∏','∐','∑','−','∓','∗','∘','√','∛','∜',
+    '∝','∞','∠','∡','∢','∧','∨','∩','∪','∫','∬','∮','∴','∵','∼','≃','≅','≈','≠','≡',
+    '≢','≤','≥','≪','≫','⊂','⊃','⊆','⊇','⊕','⊖','⊗','⊘','⊙','⊥','⋂','⋃','⋄','⋆',
+];
+
+/// Full-width kanji, two cells each
+pub const MATRIX_CHARS_KANJI: &[char] = &[
+    '日','月','火','水','木','金','土','山','川','田','人','口','目','耳','手','足','力','刀','心','竜',
+    '雨','雷','電','光','影','夢','空','風','雲','星','道','神','魔','龍','鬼','門',
+];
+
+/// Emoji that terminals draw two cells wide without a variation selector
+pub const MATRIX_CHARS_EMOJI: &[char] = &[
+    '😀','😃','😄','😁','😆','😅','😂','🤣','😊','😇','🙂','🙃','😉','😍','😎','🤓','🤖','👾','👽','💀',
+    '👻','🔥','💧','🌊','🌙','🌟','🍀','🍄','🎲','🎮','🐉','🐍','🐙','💎','💡','💾','📡','🔑','🔒','🚀',
+    '🛸',
+];
+
 pub const GLITCH_CHARS: &[char] = &['▒', '▓', '░', '█'];
 
 // ==== Animation Configuration ====
-static MIN_TRAIL_ATOMIC: AtomicU32 = AtomicU32::new(8);
-static MAX_TRAIL_ATOMIC: AtomicU32 = AtomicU32::new(25);
-const TRAIL_MIN_LIMIT: usize = 4;
-const TRAIL_MAX_LIMIT: usize = 40;
-const BASE_FRAME_DELAY: Duration = Duration::from_millis(60);
-static FRAMERATE: AtomicU32 = AtomicU32::new((12.0f32).to_bits());
+/// Rows per second a drop falls at speed 1.0
+const BASE_FALL_RATE: f32 = 12.0;
 const SPEED_VARIATION: f32 = 0.3;
+/// Trail fade exponent shared by RGB coloring and cell intensity
+const FADE_EXPONENT: f32 = 1.3;
+/// Fraction of a trail after which `dim_tail` applies
+const DIM_TAIL_START: f32 = 0.5;
+/// How far into a row the head must be before the cell ahead starts to glow
+const GLOW_START: f32 = 0.5;
+/// Brightness of the glow cell at its peak, relative to the head
+const GLOW_INTENSITY: f32 = 0.6;
+/// How long to wait for the terminal to report its background color
+const BACKGROUND_QUERY_TIMEOUT: Duration = Duration::from_millis(100);
 
 // ==== Probability Configuration ====
 const CHAR_CHANGE_PROBABILITY: f32 = 0.2;
-static GLITCH_PROBABILITY_ATOMIC: AtomicU32 = AtomicU32::new((0.003_f32).to_bits());
-static FLICKER_PROBABILITY_ATOMIC: AtomicU32 = AtomicU32::new((0.01_f32).to_bits());
-static NEW_DROP_PROBABILITY_ATOMIC: AtomicU32 = AtomicU32::new((0.05_f32).to_bits());
-static STUCK_PROBABILITY_ATOMIC: AtomicU32 = AtomicU32::new((0.02_f32).to_bits());
 const SPEED_JITTER_PROBABILITY: f64 = 0.02;
 const SPEED_JITTER_AMOUNT: f32 = 0.05;
+/// Rate the per-step probabilities above were tuned for
+const REFERENCE_STEP_RATE: f32 = 15.0;
+
+/// Scale a per-step probability tuned at `REFERENCE_STEP_RATE` to a step of `dt` seconds
+fn probability_for_step(prob: f32, dt: f32) -> f32 {
+    1.0 - (1.0 - prob).powf(dt * REFERENCE_STEP_RATE)
+}
+
+/// Hue offset for a new drop, random in the per-drop rainbow mode
+fn random_hue(config: &MatrixConfig, rng: &mut impl Rng) -> f32 {
+    match config.rainbow() {
+        Some(rainbow) if rainbow.mode() == RainbowMode::Drop => rng.random(),
+        _ => 0.0,
+    }
+}
 
 /// A falling Matrix-style character drop
 #[derive(Clone)]
-pub struct MatrixDrop<'a> {
+pub struct MatrixDrop {
     x: u16,
     y: f32,
-    prev_y: f32,
     length: usize,
     speed: f32,
     chars: Vec<char>,
-    last_update: Instant,
-    charset: &'a [char],
+    /// Leading characters that never change, e.g. a word from the wordlist
+    /// or the whole trail of source text
+    fixed: usize,
+    /// Hue offset used by the per-drop rainbow mode
+    hue: f32,
+    /// Trail color overriding the configured colors, e.g. for error lines
+    tint: Option<(u8, u8, u8)>,
 }
 
-impl<'a> MatrixDrop<'a> {
+impl MatrixDrop {
     /// Create a new Matrix drop at the given column
-    pub fn new(x: u16, _rows: u16, charset: &'a [char]) -> Self {
-        let mut rng = rng();
-        let length = rng.random_range(get_min_trail()..=get_max_trail());
+    pub fn new(x: u16, _rows: u16, config: &MatrixConfig, rng: &mut impl Rng) -> Self {
+        let mut length = rng.random_range(config.min_trail()..=config.max_trail());
         let speed = 1.0 + rng.random::<f32>() * SPEED_VARIATION;
 
-        let chars: Vec<char> = (0..length)
-            .map(|_| *charset.choose(&mut rng).unwrap())
-            .collect();
+        // Text reads top to bottom, so it is stored last character first
+        let (chars, fixed): (Vec<char>, usize) = if let Some(source) = config.source() {
+            let run = source.sample(length, rng);
+            (run.into_iter().rev().collect(), length)
+        } else {
+            // A word sits right behind the head; the trail grows to fit long phrases
+            let word = config.words().map(|words| words.choose(rng)).unwrap_or_default();
+            length = length.max(word.len());
+            let chars = word
+                .iter()
+                .rev()
+                .copied()
+                .chain((word.len()..length).map(|_| config.charset().choose(rng)))
+                .collect();
+            (chars, word.len())
+        };
+        let hue = random_hue(config, rng);
+
+        Self {
+            x,
+            y: -(length as f32),
+            length,
+            speed,
+            chars,
+            fixed,
+            hue,
+            tint: None,
+        }
+    }
+
+    /// Create a drop that carries `text` top to bottom, e.g. a line of input
+    ///
+    /// The trail is as long as the text and never changes.
+    pub fn with_text(x: u16, text: &[char], config: &MatrixConfig, rng: &mut impl Rng) -> Self {
+        let length = text.len().max(1);
+        let speed = 1.0 + rng.random::<f32>() * SPEED_VARIATION;
+        let mut chars: Vec<char> = text.iter().rev().copied().collect();
+        chars.resize(length, ' ');
 
         Self {
             x,
             y: -(length as f32),
-            prev_y: -(length as f32),
             length,
             speed,
             chars,
-            last_update: Instant::now(),
-            charset,
+            fixed: length,
+            hue: random_hue(config, rng),
+            tint: None,
         }
     }
 
-    /// Update the drop's position and characters
+    /// Color the trail `tint` instead of the configured colors
+    pub fn with_tint(mut self, tint: Option<(u8, u8, u8)>) -> Self {
+        self.tint = tint;
+        self
+    }
+
+    /// Column the drop falls in
+    pub fn x(&self) -> u16 {
+        self.x
+    }
+
+    /// Advance the drop by `dt` seconds of simulation time
     /// Returns true if the drop should be reset
-    pub fn update(&mut self, rows: u16) -> bool {
-        let now = Instant::now();
-        let dt = now.duration_since(self.last_update).as_secs_f32();
-        let fps = get_framerate();
-        
-        self.prev_y = self.y;
-        self.y += self.speed * dt * fps;
-        self.last_update = now;
+    pub fn update(&mut self, rows: u16, dt: f32, config: &MatrixConfig, rng: &mut impl Rng) -> bool {
+        self.y = self.position(config, dt);
 
         // Add some random speed variation
-        let mut rng = rng();
-        if rng.random_bool(SPEED_JITTER_PROBABILITY) {
+        let jitter_prob = probability_for_step(SPEED_JITTER_PROBABILITY as f32, dt);
+        if rng.random_bool(jitter_prob as f64) {
             let delta = rng.random_range(-SPEED_JITTER_AMOUNT..SPEED_JITTER_AMOUNT);
             self.speed = (self.speed + delta).clamp(0.5, 3.0);
         }
@@ -125,12 +243,13 @@ impl<'a> MatrixDrop<'a> {
         }
 
         // Update character changes
-        for ch in &mut self.chars {
-            if rng.random::<f32>() < CHAR_CHANGE_PROBABILITY {
+        let change_prob = probability_for_step(CHAR_CHANGE_PROBABILITY, dt);
+        for ch in &mut self.chars[self.fixed..] {
+            if rng.random::<f32>() < change_prob {
                 *ch = if rng.random_bool(0.005) {
-                    *GLITCH_CHARS.choose(&mut rng).unwrap()
+                    *GLITCH_CHARS.choose(rng).unwrap()
                 } else {
-                    *self.charset.choose(&mut rng).unwrap()
+                    config.charset().choose(rng)
                 };
             }
         }
@@ -138,52 +257,78 @@ impl<'a> MatrixDrop<'a> {
         false // Drop is still active
     }
 
-    /// Render the drop to the terminal
-    pub fn render(
-        &self, 
-        w: &mut impl Write, 
-        rows: u16, 
-        use_rgb_fade: bool, 
-        color_scheme: MatrixColorScheme,
-        sticky_chars: &mut HashMap<(u16, u16), (char, Instant)>
-    ) -> std::io::Result<()> {
-        // Clear the previous tail, but check for sticky characters first
-        let old_tail_y = (self.prev_y - self.length as f32).floor() as i32;
-        let new_tail_y = (self.y - self.length as f32).floor() as i32;
-        
-        // Clear positions between old and new tail, except sticky ones
-        let clear_start = old_tail_y.min(new_tail_y);
-        let clear_end = old_tail_y.max(new_tail_y);
-        
-        for y in clear_start..=clear_end {
-            if y >= 0 && (y as u16) < rows {
-                let pos = (self.x, y as u16);
-                if !sticky_chars.contains_key(&pos) {
-                    queue!(w, MoveTo(self.x, y as u16), Print(' '))?;
-                }
-            }
-        }
+    /// Row of the head `ahead` seconds after the last update
+    fn position(&self, config: &MatrixConfig, ahead: f32) -> f32 {
+        self.y + self.speed * config.speed() * BASE_FALL_RATE * ahead
+    }
+
+    /// Whether the trail currently covers the cell at (`x`, `y`)
+    pub fn covers(&self, x: u16, y: u16) -> bool {
+        let head = self.y.floor();
+        x == self.x && head >= 0.0 && y as f32 <= head && head - (y as f32) < self.length as f32
+    }
+
+    /// Draw the drop into a frame, `time` seconds into the simulation
+    ///
+    /// The drop is drawn `ahead` seconds past its last update, so frames
+    /// between two simulation steps show it partway to its next position.
+    pub fn render(&self, frame: &mut Frame, config: &MatrixConfig, time: f32, ahead: f32, rng: &mut impl Rng) {
+        let y = self.position(config, ahead);
+        let rows = frame.rows();
+        let color_scheme = config.color_scheme();
 
         // Get color scheme colors
         let (bright, mid, dim, dark, darkest) = color_scheme.get_colors();
+        // Trails end in the background color rather than black when one is set
+        let background = config.background_color();
+        let darkest = background.unwrap_or(darkest);
+        let background_rgb = background.and_then(color_to_rgb).unwrap_or((0, 0, 0));
+        let rainbow = config
+            .rainbow()
+            .map(|rainbow| rainbow.rgb(self.x, frame.cols(), self.hue, time));
 
         // Render current drop characters
         for (i, &ch) in self.chars.iter().enumerate() {
-            let char_y = self.y - i as f32;
+            let char_y = y - i as f32;
             if char_y < 0.0 || char_y >= rows as f32 {
                 continue;
             }
 
-            let flicker = rng().random_bool(get_flicker_probability() as f64);
-            let glitch = rng().random_bool(get_glitch_probability() as f64);
+            let flicker = rng.random_bool(config.flicker_probability() as f64);
+            let glitch = rng.random_bool(config.glitch_probability() as f64);
+
+            let intensity = 1.0 - (i as f32 / self.length as f32).powf(FADE_EXPONENT);
 
-            let color = if use_rgb_fade {
+            let color = if glitch && let Some(color) = config.glitch_color() {
+                fit_color(color, config.rgb_fade())
+            } else if i == 0 && let Some(color) = config.head_color() {
+                fit_color(color, config.rgb_fade())
+            } else if let Some(base) = self.tint.or(rainbow) {
+                if i == 0 {
+                    bright
+                } else {
+                    fit_color(fade_toward(base, background_rgb, intensity), config.rgb_fade())
+                }
+            } else if let Some(spatial) = config.spatial_gradient() {
                 if i == 0 {
                     bright
                 } else {
-                    let base_rgb = color_scheme.get_base_rgb();
-                    let alpha = 1.0 - (i as f32 / self.length as f32).powf(1.3);
-                    fade_color_rgb(base_rgb, alpha)
+                    let base = spatial.sample(self.x, char_y as u16, frame.cols(), rows);
+                    fit_color(fade_toward(base, background_rgb, intensity), config.rgb_fade())
+                }
+            } else if let Some(gradient) = config.gradient() {
+                // Gradients span the whole trail, interpolated in both modes,
+                // and blend into the background when one is set
+                let t = i as f32 / (self.length - 1).max(1) as f32;
+                match background {
+                    Some(_) => fit_color(fade_toward(gradient.sample(t), background_rgb, intensity), config.rgb_fade()),
+                    None => rgb_color(gradient.sample(t), config.rgb_fade()),
+                }
+            } else if config.rgb_fade() {
+                if i == 0 {
+                    bright
+                } else {
+                    fade_toward(color_scheme.get_base_rgb(), background_rgb, intensity)
                 }
             } else {
                 match i {
@@ -196,34 +341,62 @@ impl<'a> MatrixDrop<'a> {
             };
 
             let display_char = if glitch {
-                *GLITCH_CHARS.choose(&mut rng()).unwrap_or(&ch)
+                *GLITCH_CHARS.choose(rng).unwrap_or(&ch)
             } else if flicker {
                 ' '
             } else {
                 ch
             };
 
-            let pos = (self.x, char_y as u16);
-            // Remove any sticky character at this position (drop overwrites it)
-            sticky_chars.remove(&pos);
-            
-            queue!(w, MoveTo(self.x, char_y as u16), SetForegroundColor(color), Print(display_char))?;
+            // Bold covers the head and the next few cells; dim the back of the trail
+            let bold = config.bold_head().is_some_and(|cells| i <= cells);
+            let dim = !bold && config.dim_tail() && i as f32 >= self.length as f32 * DIM_TAIL_START;
+            let cell = FrameCell::new(display_char, color, intensity).with_attrs(CellAttrs { bold, dim });
+            frame.set(self.x, char_y as u16, cell);
         }
 
-        Ok(())
+        if config.glow() {
+            self.render_glow(frame, config, y, bright, background_rgb);
+        }
+    }
+
+    /// Light the cell ahead of the head, brightening as the head approaches it
+    fn render_glow(
+        &self,
+        frame: &mut Frame,
+        config: &MatrixConfig,
+        y: f32,
+        bright: Color,
+        background_rgb: (u8, u8, u8),
+    ) {
+        let glow_y = y.floor() + 1.0;
+        let strength = (y.fract() - GLOW_START) / (1.0 - GLOW_START);
+        if strength <= 0.0 || glow_y < 0.0 || glow_y >= frame.rows() as f32 {
+            return;
+        }
+
+        let head = config.head_color().unwrap_or(bright);
+        let head_rgb = color_to_rgb(head).unwrap_or((255, 255, 255));
+        let intensity = strength * GLOW_INTENSITY;
+        let color = fit_color(fade_toward(head_rgb, background_rgb, intensity), config.rgb_fade());
+        frame.set(self.x, glow_y as u16, FrameCell::new(self.chars[0], color, intensity));
     }
 
     /// Check if this drop should leave a stuck character when it resets
-    pub fn should_leave_sticky(&self, rows: u16) -> Option<(u16, u16, char)> {
-        if self.y > rows as f32 + self.length as f32 {
-            let mut rng = rng();
-            if rng.random::<f32>() < get_stuck_probability() {
-                // Pick the last character and a random position on screen
-                if let Some(&last_char) = self.chars.last() {
-                    let stick_y = rng.random_range(0..rows);
-                    return Some((self.x, stick_y, last_char));
-                }
-            }
+    pub fn should_leave_sticky(
+        &self,
+        rows: u16,
+        stuck_probability: f32,
+        rng: &mut impl Rng,
+    ) -> Option<(u16, u16, char)> {
+        if rows > 0
+            && self.y > rows as f32 + self.length as f32
+            && rng.random::<f32>() < stuck_probability
+            // Pick the last character and a random position on screen
+            && let Some(&last_char) = self.chars.last()
+        {
+            let stick_y = rng.random_range(0..rows);
+            return Some((self.x, stick_y, last_char));
         }
         None
     }
@@ -241,51 +414,56 @@ fn cleanup_terminal(stdout: &mut std::io::Stdout) {
     let _ = disable_raw_mode();
 }
 
-/// Main Matrix effect runner
-pub fn run_matrix(
-    initial_drops: usize,
-    use_rgb_fade: bool,
-    charset: &[char],
-    fps: u32,
-    enable_stuck: bool,
-    color_scheme: MatrixColorScheme,
-) -> std::io::Result<()> {
-    let running = Arc::new(AtomicBool::new(true));
-    let r = running.clone();
-
-    // Set up Ctrl+C handler
-    ctrlc::set_handler(move || {
-        r.store(false, Ordering::SeqCst);
-    }).map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
-
-    set_framerate(fps as f32);
-
-    let mut stdout = stdout();
-    enable_raw_mode()?;
-    let (mut cols, mut rows) = size()?;
-    let mut rng = rng();
-    let mut drops: Vec<Option<MatrixDrop>> = vec![None; cols as usize];
-    let mut sticky_chars: HashMap<(u16, u16), (char, Instant)> = HashMap::new();
+/// How a `run_matrix` session went, e.g. for printing diagnostics
+#[derive(Clone, Debug)]
+pub struct RunStats {
+    /// Seed in use; pass it to `MatrixConfig::with_seed` to replay the run
+    pub seed: u64,
+    pub color_depth: ColorDepth,
+    /// Background the trails faded toward, configured or detected
+    pub background: Option<Color>,
+    pub frames: u64,
+    pub dropped_frames: u64,
+    pub target_fps: f64,
+    pub average_fps: f64,
+}
 
-    // Randomize initial drop positions
-    let mut columns: Vec<u16> = (0..cols).collect();
-    columns.shuffle(&mut rng);
+/// Run the Matrix effect in the current terminal until the user quits
+///
+/// Ctrl+C, Esc and `q` quit; raw mode delivers Ctrl+C as a key press, so no
+/// signal handler is installed and this can be called any number of times.
+pub fn run_matrix(config: MatrixConfig) -> std::io::Result<RunStats> {
+    run_matrix_with_feed(config, None)
+}
 
-    for &col in columns.iter().take(initial_drops.min(cols as usize)) {
-        drops[col as usize] = Some(MatrixDrop::new(col, rows, charset));
+/// Like `run_matrix`, with drops spawned for text arriving on `feed`
+pub fn run_matrix_with_feed(
+    mut config: MatrixConfig,
+    mut feed: Option<Feed>,
+) -> std::io::Result<RunStats> {
+    enable_raw_mode()?;
+    let (cols, rows) = size()?;
+    if config.background() == Some(Background::Auto) {
+        // Terminals that don't answer keep the default fade to black
+        let detected = query_background(BACKGROUND_QUERY_TIMEOUT)
+            .map(|(r, g, b)| Background::Color(Color::Rgb { r, g, b }));
+        config = config.with_background(detected);
+    }
+    let depth = config.color_depth().unwrap_or_else(ColorDepth::detect);
+    if depth == ColorDepth::Mono {
+        // Without colors, intensity has to come from glyphs and attributes
+        config = config.with_mono(true);
     }
+    let mut renderer = CrosstermRenderer::new(stdout(), cols, rows).with_color_depth(depth);
+    let mut simulation = MatrixSimulation::new(config, cols, rows);
+    let mut scheduler = FrameScheduler::new(simulation.config().fps());
 
-    execute!(stdout, EnterAlternateScreen, Hide, Clear(ClearType::All))?;
-    let mut last_spawn_check = Instant::now();
+    execute!(renderer.writer(), EnterAlternateScreen, Hide, Clear(ClearType::All))?;
 
     // Main animation loop
     'main: loop {
-        if !running.load(Ordering::SeqCst) {
-            break 'main;
-        }
-
-        // Handle input events
-        if poll(Duration::from_millis(1))? {
+        // Handle input events while waiting for the next frame
+        if poll(scheduler.time_until_next_frame())? {
             match read()? {
                 Event::Key(key) => match (key.code, key.modifiers) {
                     (KeyCode::Char('q'), _) | (KeyCode::Char('Q'), _) | (KeyCode::Esc, _) => break 'main,
@@ -293,144 +471,43 @@ pub fn run_matrix(
                     _ => {}
                 },
                 Event::Resize(new_cols, new_rows) => {
-                    cols = new_cols;
-                    rows = new_rows;
-                    sticky_chars.clear();
-                    execute!(stdout, Clear(ClearType::All))?;
-                    drops = (0..cols)
-                        .map(|x| if rng.random::<f32>() < 0.3 { 
-                            Some(MatrixDrop::new(x, rows, charset)) 
-                        } else { 
-                            None 
-                        })
-                        .collect();
+                    renderer.resize(new_cols, new_rows)?;
+                    simulation.resize(new_cols, new_rows);
+                    scheduler.reset();
                 }
                 _ => {}
             }
-        }
-
-        // Clean up old stuck characters (remove after 10 seconds)
-        if enable_stuck {
-            let now = Instant::now();
-            sticky_chars.retain(|_, (_, timestamp)| {
-                now.duration_since(*timestamp).as_secs() < 10
-            });
-        }
-
-        // Spawn new drops periodically
-        let now = Instant::now();
-        if now.duration_since(last_spawn_check).as_secs_f32() > 0.2 {
-            for (x, drop_slot) in drops.iter_mut().enumerate() {
-                if drop_slot.is_none() && rng.random::<f32>() < get_new_drop_probability() {
-                    *drop_slot = Some(MatrixDrop::new(x as u16, rows, charset));
-                }
+            // A steady stream of events, e.g. while resizing, must not hold
+            // frames back: once the frame is due, draw it
+            if !scheduler.time_until_next_frame().is_zero() {
+                continue 'main;
             }
-            last_spawn_check = now;
         }
 
-        // Get stuck character color
-        let (_, _, stuck_color, _, _) = color_scheme.get_colors();
-
-        // Render stuck characters first (so drops can overwrite them)
-        if enable_stuck {
-            for (&(x, y), &(ch, _)) in sticky_chars.iter() {
-                if y < rows {
-                    queue!(stdout, MoveTo(x, y), SetForegroundColor(stuck_color), Print(ch))?;
-                }
+        if let Some(feed) = feed.as_mut() {
+            for text in feed.drain() {
+                simulation.feed(&text);
             }
-        }
-
-        // Update and render drops
-        for drop_slot in drops.iter_mut() {
-            if let Some(drop) = drop_slot {
-                let should_reset = drop.update(rows);
-                
-                // Check if drop should leave a stuck character before resetting
-                if enable_stuck && should_reset {
-                    if let Some((x, y, ch)) = drop.should_leave_sticky(rows) {
-                        sticky_chars.insert((x, y), (ch, Instant::now()));
-                    }
-                }
-                
-                if should_reset {
-                    *drop = MatrixDrop::new(drop.x, rows, charset);
-                } else {
-                    drop.render(&mut stdout, rows, use_rgb_fade, color_scheme, &mut sticky_chars)?;
-                }
+            if feed.is_closed() && feed.exit_on_close() && simulation.is_idle() {
+                break 'main;
             }
         }
 
-        stdout.flush()?;
-        sleep(BASE_FRAME_DELAY);
-    }
-
-    cleanup_terminal(&mut stdout);
-    println!("Goodbye from the Matrix...");
-    Ok(())
-}
+        simulation.tick(scheduler.begin_frame());
 
-// ==== Configuration Getters and Setters ====
-
-pub fn set_framerate(fps: f32) {
-    let fps = fps.clamp(1.0, 15.0);
-    FRAMERATE.store(fps.to_bits(), Ordering::Relaxed);
-}
-
-pub fn get_framerate() -> f32 {
-    f32::from_bits(FRAMERATE.load(Ordering::Relaxed))
-}
-
-pub fn set_glitch_probability(prob: f32) {
-    let prob = prob.clamp(0.0, 1.0);
-    GLITCH_PROBABILITY_ATOMIC.store(prob.to_bits(), Ordering::Relaxed);
-}
-
-pub fn get_glitch_probability() -> f32 {
-    f32::from_bits(GLITCH_PROBABILITY_ATOMIC.load(Ordering::Relaxed))
-}
-
-pub fn set_flicker_probability(prob: f32) {
-    let prob = prob.clamp(0.0, 1.0);
-    FLICKER_PROBABILITY_ATOMIC.store(prob.to_bits(), Ordering::Relaxed);
-}
-
-pub fn get_flicker_probability() -> f32 {
-    f32::from_bits(FLICKER_PROBABILITY_ATOMIC.load(Ordering::Relaxed))
-}
-
-pub fn set_stuck_probability(prob: f32) {
-    let prob = prob.clamp(0.0, 1.0);
-    STUCK_PROBABILITY_ATOMIC.store(prob.to_bits(), Ordering::Relaxed);
-}
-
-pub fn get_stuck_probability() -> f32 {
-    f32::from_bits(STUCK_PROBABILITY_ATOMIC.load(Ordering::Relaxed))
-}
-
-pub fn set_min_trail(len: usize) {
-    let clamped = len.clamp(TRAIL_MIN_LIMIT, TRAIL_MAX_LIMIT.min(get_max_trail()));
-    MIN_TRAIL_ATOMIC.store(clamped as u32, Ordering::Relaxed);
-}
-
-pub fn get_min_trail() -> usize {
-    MIN_TRAIL_ATOMIC.load(Ordering::Relaxed) as usize
-}
-
-pub fn set_max_trail(len: usize) {
-    let clamped = len.clamp(get_min_trail().max(TRAIL_MIN_LIMIT), TRAIL_MAX_LIMIT);
-    MAX_TRAIL_ATOMIC.store(clamped as u32, Ordering::Relaxed);
-}
-
-pub fn get_max_trail() -> usize {
-    MAX_TRAIL_ATOMIC.load(Ordering::Relaxed) as usize
-}
-
-pub fn set_new_drop_probability(prob: f32) {
-    let prob = prob.clamp(0.0, 1.0);
-    NEW_DROP_PROBABILITY_ATOMIC.store(prob.to_bits(), Ordering::Relaxed);
-}
+        simulation.render(&mut renderer);
+        renderer.flush()?;
+        scheduler.end_frame();
+    }
 
-pub fn get_new_drop_probability() -> f32 {
-    f32::from_bits(NEW_DROP_PROBABILITY_ATOMIC.load(Ordering::Relaxed))
+    cleanup_terminal(renderer.writer());
+    Ok(RunStats {
+        seed: simulation.seed(),
+        color_depth: renderer.color_depth(),
+        background: simulation.config().background_color(),
+        frames: scheduler.frames(),
+        dropped_frames: scheduler.dropped_frames(),
+        target_fps: scheduler.target_fps(),
+        average_fps: scheduler.average_fps(),
+    })
 }
-
@@ -0,0 +1,232 @@
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+
+use crate::charset::{char_width, is_drawable, Charset};
+use crate::config::classic_charset;
+use crate::matrix::{
+    MATRIX_CHARS_ALPHANUMERIC, MATRIX_CHARS_BINARY, MATRIX_CHARS_BOX_DRAWING,
+    MATRIX_CHARS_BRAILLE, MATRIX_CHARS_CYRILLIC, MATRIX_CHARS_DEVANAGARI, MATRIX_CHARS_DIGITS,
+    MATRIX_CHARS_EMOJI, MATRIX_CHARS_GREEK, MATRIX_CHARS_HANGUL, MATRIX_CHARS_HEX,
+    MATRIX_CHARS_KANJI, MATRIX_CHARS_KATAKANA, MATRIX_CHARS_MATH, MATRIX_CHARS_RUNIC,
+    MATRIX_CHARS_SYMBOLS,
+};
+
+/// File extension of user-defined palettes in the palette directory
+pub const PALETTE_FILE_EXTENSION: &str = "txt";
+
+/// A named set of characters drops can be made of
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Palette {
+    pub name: String,
+    pub description: String,
+    pub chars: Cow<'static, [char]>,
+    /// Loaded from a file rather than built in
+    pub user: bool,
+}
+
+impl Palette {
+    fn builtin(name: &str, description: &str, chars: impl Into<Cow<'static, [char]>>) -> Self {
+        Self {
+            name: name.to_string(),
+            description: description.to_string(),
+            chars: chars.into(),
+            user: false,
+        }
+    }
+
+    /// Cells the widest character takes up
+    pub fn width(&self) -> usize {
+        self.chars.iter().copied().map(char_width).max().unwrap_or(1)
+    }
+
+    /// Parse a user palette file
+    ///
+    /// Every visible character of the file is part of the palette, except on
+    /// lines starting with `#`, which are comments. The first comment is used
+    /// as the description. Zero-width characters such as combining marks are
+    /// skipped.
+    pub fn from_file_contents(name: &str, contents: &str) -> Result<Self, String> {
+        validate_name(name)?;
+
+        let mut description = None;
+        let mut chars = Vec::new();
+        for line in contents.lines() {
+            if let Some(comment) = line.trim_start().strip_prefix('#') {
+                description.get_or_insert_with(|| comment.trim().to_string());
+                continue;
+            }
+            chars.extend(line.chars().filter(|&c| is_drawable(c)));
+        }
+        if chars.is_empty() {
+            return Err(format!("palette '{name}' has no characters"));
+        }
+
+        Ok(Self {
+            name: normalize(name),
+            description: description.unwrap_or_default(),
+            chars: chars.into(),
+            user: true,
+        })
+    }
+}
+
+/// Built-in palettes plus any loaded from the user's palette directory
+#[derive(Clone, Debug)]
+pub struct PaletteRegistry {
+    palettes: Vec<Palette>,
+}
+
+impl Default for PaletteRegistry {
+    fn default() -> Self {
+        Self {
+            palettes: vec![
+                Palette::builtin(
+                    "classic",
+                    "The original mix of katakana, alphanumeric, symbols and greek",
+                    classic_charset(),
+                ),
+                Palette::builtin("katakana", "Half-width katakana", MATRIX_CHARS_KATAKANA),
+                Palette::builtin("alphanumeric", "Digits and uppercase Latin letters", MATRIX_CHARS_ALPHANUMERIC),
+                Palette::builtin("digits", "Decimal digits", MATRIX_CHARS_DIGITS),
+                Palette::builtin("symbols", "Box drawing and typographic symbols", MATRIX_CHARS_SYMBOLS),
+                Palette::builtin("greek", "Uppercase Greek letters", MATRIX_CHARS_GREEK),
+                Palette::builtin("binary", "Ones and zeros", MATRIX_CHARS_BINARY),
+                Palette::builtin("hex", "Hexadecimal digits", MATRIX_CHARS_HEX),
+                Palette::builtin("braille", "Braille dot patterns", MATRIX_CHARS_BRAILLE.collect::<Vec<_>>()),
+                Palette::builtin("box-drawing", "Box drawing lines and corners", MATRIX_CHARS_BOX_DRAWING.collect::<Vec<_>>()),
+                Palette::builtin("runic", "Futhark runes", MATRIX_CHARS_RUNIC.collect::<Vec<_>>()),
+                Palette::builtin("cyrillic", "Uppercase Cyrillic letters", MATRIX_CHARS_CYRILLIC),
+                Palette::builtin("hangul", "Korean Hangul syllables (wide)", MATRIX_CHARS_HANGUL),
+                Palette::builtin("devanagari", "Devanagari vowels and consonants", MATRIX_CHARS_DEVANAGARI),
+                Palette::builtin("math", "Mathematical operators", MATRIX_CHARS_MATH),
+                Palette::builtin("kanji", "Full-width kanji (wide)", MATRIX_CHARS_KANJI),
+                Palette::builtin("emoji", "Emoji (wide)", MATRIX_CHARS_EMOJI),
+            ],
+        }
+    }
+}
+
+impl PaletteRegistry {
+    /// Only the built-in palettes
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a palette, replacing any palette of the same name
+    pub fn insert(&mut self, palette: Palette) {
+        match self.palettes.iter_mut().find(|p| p.name == palette.name) {
+            Some(existing) => *existing = palette,
+            None => self.palettes.push(palette),
+        }
+    }
+
+    /// Load every `*.txt` file in `dir` as a palette named after the file
+    ///
+    /// A missing directory is not an error. Files that fail to load are
+    /// skipped and reported in the returned list of errors.
+    pub fn load_dir(&mut self, dir: &Path) -> Vec<String> {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return Vec::new();
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == PALETTE_FILE_EXTENSION))
+            .collect();
+        paths.sort();
+
+        let mut errors = Vec::new();
+        for path in paths {
+            let name = path.file_stem().unwrap_or_default().to_string_lossy();
+            let loaded = fs::read_to_string(&path)
+                .map_err(|err| err.to_string())
+                .and_then(|contents| Palette::from_file_contents(&name, &contents));
+            match loaded {
+                Ok(palette) => self.insert(palette),
+                Err(err) => errors.push(format!("{}: {err}", path.display())),
+            }
+        }
+        errors
+    }
+
+    /// Look up a palette by name, ignoring case
+    pub fn find(&self, name: &str) -> Option<&Palette> {
+        let name = normalize(name);
+        self.palettes.iter().find(|palette| palette.name == name)
+    }
+
+    /// Look up a palette's characters, with an error suggesting close names
+    pub fn lookup(&self, name: &str) -> Result<Vec<char>, String> {
+        if let Some(palette) = self.find(name) {
+            return Ok(palette.chars.to_vec());
+        }
+        let wanted = normalize(name);
+        let closest = self
+            .palettes
+            .iter()
+            .map(|palette| (edit_distance(&wanted, &palette.name), &palette.name))
+            .filter(|&(distance, _)| distance <= 2)
+            .min();
+        Err(match closest {
+            Some((_, suggestion)) => {
+                format!("unknown palette '{name}', did you mean '{suggestion}'? (see --list-palettes)")
+            }
+            None => format!("unknown palette '{name}', expected one of: {}", self.names().join(", ")),
+        })
+    }
+
+    /// Parse a palette composition such as `katakana+greek` or
+    /// `katakana:3,digits:1` against this registry
+    pub fn parse(&self, spec: &str) -> Result<Charset, String> {
+        Charset::parse(spec, |name| self.lookup(name))
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.palettes.iter().map(|palette| palette.name.as_str()).collect()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Palette> {
+        self.palettes.iter()
+    }
+}
+
+/// Where user palettes are read from: `$XDG_CONFIG_HOME/make-it-rain/palettes`,
+/// falling back to `~/.config/make-it-rain/palettes`
+pub fn user_palette_dir() -> Option<PathBuf> {
+    let config = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config.join("make-it-rain").join("palettes"))
+}
+
+fn normalize(name: &str) -> String {
+    name.trim().to_lowercase().replace('_', "-")
+}
+
+/// Names end up in `--palette` specs, so they can't use its separators
+fn validate_name(name: &str) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("palette name is empty".to_string());
+    }
+    if let Some(c) = name.chars().find(|&c| matches!(c, '+' | ',' | ':') || c.is_whitespace()) {
+        return Err(format!("palette name '{name}' cannot contain '{c}'"));
+    }
+    Ok(())
+}
+
+/// Levenshtein distance between two names
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = (above + 1).min(row[j] + 1).min(diagonal + usize::from(ca != cb));
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
@@ -0,0 +1,84 @@
+use crate::colors::hsv_to_rgb;
+
+/// What the rainbow hue follows
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RainbowMode {
+    /// The whole screen shares one hue that shifts over time
+    #[default]
+    Time,
+    /// Hue sweeps across the columns and scrolls over time
+    Column,
+    /// Every drop gets its own hue, all shifting over time
+    Drop,
+}
+
+impl RainbowMode {
+    pub const NAMES: &[&str] = &["time", "column", "drop"];
+
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "time" => Ok(Self::Time),
+            "column" | "columns" => Ok(Self::Column),
+            "drop" | "drops" => Ok(Self::Drop),
+            other => Err(format!(
+                "unknown rainbow mode '{other}', expected one of: {}",
+                Self::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+/// Hue-cycling base color for drops
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rainbow {
+    mode: RainbowMode,
+    speed: f32,
+    saturation: f32,
+}
+
+impl Rainbow {
+    pub fn new(mode: RainbowMode) -> Self {
+        Self { mode, speed: 0.1, saturation: 1.0 }
+    }
+
+    /// Set how many full hue cycles happen per second (may be negative)
+    pub fn with_speed(mut self, cycles_per_second: f32) -> Self {
+        self.speed = cycles_per_second;
+        self
+    }
+
+    /// Set the color saturation (0.0 = grey, 1.0 = fully saturated)
+    pub fn with_saturation(mut self, saturation: f32) -> Self {
+        self.saturation = saturation.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn mode(&self) -> RainbowMode {
+        self.mode
+    }
+
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    pub fn saturation(&self) -> f32 {
+        self.saturation
+    }
+
+    /// Hue (0.0-1.0) for a drop in column `x` of `cols`, `time` seconds in
+    ///
+    /// `drop_hue` is the drop's own offset, used in `Drop` mode.
+    pub fn hue(&self, x: u16, cols: u16, drop_hue: f32, time: f32) -> f32 {
+        let offset = match self.mode {
+            RainbowMode::Time => 0.0,
+            RainbowMode::Column => x as f32 / cols.max(1) as f32,
+            RainbowMode::Drop => drop_hue,
+        };
+        (offset + time * self.speed).rem_euclid(1.0)
+    }
+
+    /// Full-brightness base color for a drop; trails fade from it as usual
+    pub fn rgb(&self, x: u16, cols: u16, drop_hue: f32, time: f32) -> (u8, u8, u8) {
+        hsv_to_rgb(self.hue(x, cols, drop_hue, time), self.saturation, 1.0)
+    }
+}
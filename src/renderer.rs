@@ -0,0 +1,194 @@
+use crossterm::{
+    execute,
+    terminal::{Clear, ClearType},
+};
+use std::io::Write;
+
+use crate::buffer::{Cell, ScreenBuffer};
+use crate::capability::ColorDepth;
+use crate::charset::char_width;
+
+/// A surface the Matrix simulation can draw onto
+///
+/// A frame is drawn by calling `clear`, then `put_cell` for every visible
+/// cell, then `flush` to present it.
+pub trait Renderer {
+    /// Write a cell into the current frame, ignoring out-of-bounds positions
+    fn put_cell(&mut self, x: u16, y: u16, cell: Cell);
+
+    /// Present the current frame
+    fn flush(&mut self) -> std::io::Result<()>;
+
+    /// Size of the drawable area as (columns, rows)
+    fn size(&self) -> (u16, u16);
+
+    /// Start a new, blank frame
+    fn clear(&mut self);
+
+    /// Change the size of the drawable area
+    fn resize(&mut self, cols: u16, rows: u16) -> std::io::Result<()>;
+}
+
+/// Renderer backend that writes to a terminal through crossterm
+///
+/// Frames are diffed against what is already on screen, so only changed
+/// cells are written to the underlying writer. Colors are reduced to what
+/// the terminal's color depth can show as they are drawn.
+pub struct CrosstermRenderer<W: Write> {
+    out: W,
+    buffer: ScreenBuffer,
+    cols: u16,
+    rows: u16,
+    depth: ColorDepth,
+}
+
+impl<W: Write> CrosstermRenderer<W> {
+    pub fn new(out: W, cols: u16, rows: u16) -> Self {
+        Self {
+            out,
+            buffer: ScreenBuffer::new(cols, rows),
+            cols,
+            rows,
+            depth: ColorDepth::TrueColor,
+        }
+    }
+
+    /// Quantize colors for a terminal with the given color depth
+    pub fn with_color_depth(mut self, depth: ColorDepth) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    pub fn color_depth(&self) -> ColorDepth {
+        self.depth
+    }
+
+    /// Access the underlying writer, e.g. for terminal setup and teardown
+    pub fn writer(&mut self) -> &mut W {
+        &mut self.out
+    }
+}
+
+impl<W: Write> Renderer for CrosstermRenderer<W> {
+    fn put_cell(&mut self, x: u16, y: u16, mut cell: Cell) {
+        cell.fg = self.depth.quantize(cell.fg);
+        self.buffer.set(x, y, cell);
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        // Only the cells that changed since the last frame reach the terminal
+        self.buffer.flush(&mut self.out)?;
+        self.out.flush()
+    }
+
+    fn size(&self) -> (u16, u16) {
+        (self.cols, self.rows)
+    }
+
+    fn clear(&mut self) {
+        self.buffer.clear();
+    }
+
+    fn resize(&mut self, cols: u16, rows: u16) -> std::io::Result<()> {
+        self.cols = cols;
+        self.rows = rows;
+        execute!(self.out, Clear(ClearType::All))?;
+        self.buffer.resize(cols, rows);
+        Ok(())
+    }
+}
+
+/// Renderer backend that keeps frames in memory
+///
+/// Useful for tests, recorders and frontends that do their own drawing.
+/// The last flushed frame is available through `cell` and `lines`.
+#[derive(Clone, Debug)]
+pub struct MemoryRenderer {
+    cols: u16,
+    rows: u16,
+    current: Vec<Cell>,
+    presented: Vec<Cell>,
+    frames: u64,
+}
+
+impl MemoryRenderer {
+    pub fn new(cols: u16, rows: u16) -> Self {
+        let len = cols as usize * rows as usize;
+        Self {
+            cols,
+            rows,
+            current: vec![Cell::BLANK; len],
+            presented: vec![Cell::BLANK; len],
+            frames: 0,
+        }
+    }
+
+    /// Cell at the given position in the last flushed frame
+    pub fn cell(&self, x: u16, y: u16) -> Option<&Cell> {
+        if x < self.cols && y < self.rows {
+            self.presented.get(y as usize * self.cols as usize + x as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Characters of the last flushed frame, one string per row
+    ///
+    /// Cells covered by a wide glyph to their left are left out, so each
+    /// line is as wide on a terminal as the frame.
+    pub fn lines(&self) -> Vec<String> {
+        if self.cols == 0 {
+            return vec![String::new(); self.rows as usize];
+        }
+        self.presented
+            .chunks(self.cols as usize)
+            .map(|row| {
+                let mut line = String::new();
+                let mut covered = 0;
+                for cell in row {
+                    if covered > 0 {
+                        covered -= 1;
+                        continue;
+                    }
+                    line.push(cell.ch);
+                    covered = char_width(cell.ch).saturating_sub(1);
+                }
+                line
+            })
+            .collect()
+    }
+
+    /// Number of frames flushed so far
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+}
+
+impl Renderer for MemoryRenderer {
+    fn put_cell(&mut self, x: u16, y: u16, cell: Cell) {
+        if x < self.cols && y < self.rows {
+            self.current[y as usize * self.cols as usize + x as usize] = cell;
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.presented.copy_from_slice(&self.current);
+        self.frames += 1;
+        Ok(())
+    }
+
+    fn size(&self) -> (u16, u16) {
+        (self.cols, self.rows)
+    }
+
+    fn clear(&mut self) {
+        self.current.fill(Cell::BLANK);
+    }
+
+    fn resize(&mut self, cols: u16, rows: u16) -> std::io::Result<()> {
+        let frames = self.frames;
+        *self = Self::new(cols, rows);
+        self.frames = frames;
+        Ok(())
+    }
+}
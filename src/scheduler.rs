@@ -0,0 +1,103 @@
+use std::time::{Duration, Instant};
+
+/// Fixed length of one simulation step (60 steps per second)
+pub const SIMULATION_STEP: Duration = Duration::from_nanos(1_000_000_000 / 60);
+
+pub const MIN_FPS: u32 = 1;
+pub const MAX_FPS: u32 = 144;
+
+/// Longest stall the simulation will try to catch up on in one frame
+const MAX_CATCH_UP: Duration = Duration::from_millis(250);
+
+/// Frame scheduler that keeps the simulation in step with real time
+///
+/// Each frame hands the simulation the time since the previous one.
+/// `MatrixSimulation::tick` accumulates it and advances in whole
+/// `SIMULATION_STEP`s, drawing drops partway to their next position in
+/// between, so every frame shows new positions at any rate up to `MAX_FPS`.
+/// Render time is accounted for by scheduling against absolute deadlines
+/// instead of sleeping a fixed delay.
+pub struct FrameScheduler {
+    frame_interval: Duration,
+    next_frame: Instant,
+    last_frame: Instant,
+    max_catch_up: Duration,
+    frames: u64,
+    dropped: u64,
+    started: Instant,
+}
+
+impl FrameScheduler {
+    pub fn new(fps: u32) -> Self {
+        let fps = fps.clamp(MIN_FPS, MAX_FPS);
+        let frame_interval = Duration::from_secs_f64(1.0 / fps as f64);
+        let now = Instant::now();
+        Self {
+            frame_interval,
+            next_frame: now,
+            last_frame: now,
+            max_catch_up: MAX_CATCH_UP.max(frame_interval * 2),
+            frames: 0,
+            dropped: 0,
+            started: now,
+        }
+    }
+
+    /// Time left until the next frame is due
+    pub fn time_until_next_frame(&self) -> Duration {
+        self.next_frame.saturating_duration_since(Instant::now())
+    }
+
+    /// Start a frame, returning the time to pass to `MatrixSimulation::tick`
+    ///
+    /// Stalls longer than the catch-up limit, e.g. a suspended process, are
+    /// cut short rather than replayed.
+    pub fn begin_frame(&mut self) -> Duration {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_frame).min(self.max_catch_up);
+        self.last_frame = now;
+        elapsed
+    }
+
+    /// Finish a frame and schedule the next deadline
+    ///
+    /// If rendering overran one or more whole frame intervals, those frames
+    /// are counted as dropped and skipped rather than rendered back to back.
+    pub fn end_frame(&mut self) {
+        self.frames += 1;
+        self.next_frame += self.frame_interval;
+
+        let now = Instant::now();
+        if now > self.next_frame {
+            let behind = now.duration_since(self.next_frame);
+            let missed = (behind.as_nanos() / self.frame_interval.as_nanos()) as u32;
+            self.dropped += missed as u64;
+            self.next_frame += self.frame_interval * missed;
+        }
+    }
+
+    /// Discard any pending catch-up time, e.g. after a resize
+    pub fn reset(&mut self) {
+        let now = Instant::now();
+        self.next_frame = now;
+        self.last_frame = now;
+    }
+
+    pub fn target_fps(&self) -> f64 {
+        1.0 / self.frame_interval.as_secs_f64()
+    }
+
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    pub fn dropped_frames(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Average presented frame rate since the scheduler was created
+    pub fn average_fps(&self) -> f64 {
+        let elapsed = self.started.elapsed().as_secs_f64();
+        if elapsed > 0.0 { self.frames as f64 / elapsed } else { 0.0 }
+    }
+}
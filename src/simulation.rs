@@ -0,0 +1,318 @@
+use rand::{rng, RngExt, SeedableRng};
+use rand::prelude::SliceRandom;
+use rand::rngs::Xoshiro256PlusPlus;
+use std::{
+    collections::{HashMap, VecDeque},
+    time::Duration,
+};
+
+use crate::charset::{char_width, is_drawable, narrow_fallback};
+use crate::colors::{fit_color, rgb_color};
+use crate::config::MatrixConfig;
+use crate::frame::{Frame, FrameCell};
+use crate::matrix::MatrixDrop;
+use crate::renderer::Renderer;
+use crate::scheduler::SIMULATION_STEP;
+
+/// How often empty columns get a chance to spawn a new drop, in seconds
+const SPAWN_CHECK_INTERVAL: f32 = 0.2;
+/// Share of columns that get a drop straight away after a resize
+const RESIZE_DROP_PROBABILITY: f32 = 0.3;
+/// How long a stuck character stays on screen, in seconds
+const STICKY_LIFETIME: f32 = 10.0;
+/// Brightness reported for stuck characters
+const STICKY_INTENSITY: f32 = 0.4;
+/// Where along a trail gradient stuck characters take their color from
+const STICKY_GRADIENT_POSITION: f32 = 0.5;
+/// Most fed drops waiting for a free column; older ones are dropped first
+/// so a fast input stream stays live instead of falling behind
+const MAX_PENDING_DROPS: usize = 512;
+/// Mixed into the seed of the per-frame effects generator
+const EFFECTS_SEED_MIX: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// Random number generator owned by each simulation
+///
+/// A fixed algorithm (rather than `StdRng`) keeps seeded runs reproducible
+/// across `rand` releases.
+pub type MatrixRng = Xoshiro256PlusPlus;
+
+/// Fed text waiting to fall, with the color its highlight rule gave it
+struct PendingDrop {
+    text: Vec<char>,
+    tint: Option<(u8, u8, u8)>,
+}
+
+/// A self-contained Matrix rain instance
+///
+/// Owns its configuration, drops and stuck characters, so any number of
+/// simulations with different settings can run side by side. Drive it with
+/// `tick` from any event loop and read the result back through `frame`.
+///
+/// All randomness comes from a generator seeded from `MatrixConfig::seed`,
+/// so the same seed, screen size and sequence of ticks always produce the
+/// same frames.
+pub struct MatrixSimulation {
+    config: MatrixConfig,
+    seed: u64,
+    rng: MatrixRng,
+    /// Number of steps simulated so far, used to seed per-frame effects
+    steps: u64,
+    /// Ticked time not yet simulated, less than one step
+    accumulator: Duration,
+    cols: u16,
+    rows: u16,
+    /// One slot per lane of `MatrixConfig::column_stride` columns
+    drops: Vec<Option<MatrixDrop>>,
+    /// Stuck characters and how long they have been on screen, in seconds
+    sticky_chars: HashMap<(u16, u16), (char, f32)>,
+    spawn_timer: f32,
+    /// Fed text waiting for a free column, one entry per drop
+    pending: VecDeque<PendingDrop>,
+    frame: Frame,
+}
+
+impl MatrixSimulation {
+    /// Create a simulation for a screen of the given size
+    pub fn new(config: MatrixConfig, cols: u16, rows: u16) -> Self {
+        let seed = config.seed().unwrap_or_else(|| rng().random());
+        let mut rng = MatrixRng::seed_from_u64(seed);
+        let stride = config.column_stride();
+        let lanes = cols / stride;
+        let mut drops: Vec<Option<MatrixDrop>> = vec![None; lanes as usize];
+
+        // Randomize initial drop positions
+        let mut columns: Vec<u16> = (0..lanes).collect();
+        columns.shuffle(&mut rng);
+
+        let initial_drops = if config.feed_only() { 0 } else { config.initial_drops() };
+        for &lane in columns.iter().take(initial_drops.min(lanes as usize)) {
+            drops[lane as usize] = Some(MatrixDrop::new(lane * stride, rows, &config, &mut rng));
+        }
+
+        Self {
+            config,
+            seed,
+            rng,
+            steps: 0,
+            accumulator: Duration::ZERO,
+            cols,
+            rows,
+            drops,
+            sticky_chars: HashMap::new(),
+            spawn_timer: 0.0,
+            pending: VecDeque::new(),
+            frame: Frame::new(cols, rows),
+        }
+    }
+
+    pub fn config(&self) -> &MatrixConfig {
+        &self.config
+    }
+
+    /// Seed in use, including the randomly chosen one if none was configured
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Size of the simulated screen as (columns, rows)
+    pub fn size(&self) -> (u16, u16) {
+        (self.cols, self.rows)
+    }
+
+    /// Start over on a screen of a new size
+    pub fn resize(&mut self, cols: u16, rows: u16) {
+        let rng = &mut self.rng;
+        let stride = self.config.column_stride();
+        self.cols = cols;
+        self.rows = rows;
+        self.sticky_chars.clear();
+        self.frame = Frame::new(cols, rows);
+        let feed_only = self.config.feed_only();
+        self.drops = (0..cols / stride)
+            .map(|lane| if !feed_only && rng.random::<f32>() < RESIZE_DROP_PROBABILITY {
+                Some(MatrixDrop::new(lane * stride, rows, &self.config, rng))
+            } else {
+                None
+            })
+            .collect();
+    }
+
+    /// Queue text to fall as new drops, e.g. a line of a log
+    ///
+    /// Whitespace becomes gaps and text longer than the maximum trail is
+    /// split over several drops. Wide glyphs are swapped for narrow stand-ins
+    /// unless the charset already spaces drops two cells apart. Each drop starts in a random free column at
+    /// the next step, so drops appear as fast as text arrives. Text matching
+    /// a rule of `MatrixConfig::highlighter` falls in that rule's color.
+    pub fn feed(&mut self, text: &str) {
+        let tint = self.config.highlighter().and_then(|highlighter| highlighter.color(text));
+        // Wide glyphs would spill into the next lane unless lanes are two cells wide
+        let narrow_lanes = self.config.column_stride() < 2;
+        let chars: Vec<char> = text
+            .trim()
+            .chars()
+            .filter_map(|c| match c {
+                c if c.is_whitespace() => Some(' '),
+                c if narrow_lanes && char_width(c) > 1 => Some(narrow_fallback(c)),
+                c if is_drawable(c) => Some(c),
+                _ => None,
+            })
+            .collect();
+        for chunk in chars.chunks(self.config.max_trail()) {
+            if self.pending.len() == MAX_PENDING_DROPS {
+                self.pending.pop_front();
+            }
+            self.pending.push_back(PendingDrop { text: chunk.to_vec(), tint });
+        }
+    }
+
+    /// Number of fed drops still waiting for a free column
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Whether nothing is falling and no fed text is waiting
+    pub fn is_idle(&self) -> bool {
+        self.pending.is_empty() && self.drops.iter().all(Option::is_none)
+    }
+
+    /// Advance the simulation by `dt` and compose a new frame
+    ///
+    /// The state only ever moves on in whole `SIMULATION_STEP`s; time left
+    /// over carries into the next tick, and drops are drawn that far past
+    /// their last step. The animation is the same however often it is ticked.
+    pub fn tick(&mut self, dt: Duration) {
+        self.accumulator += dt;
+        while self.accumulator >= SIMULATION_STEP {
+            self.accumulator -= SIMULATION_STEP;
+            self.step(SIMULATION_STEP.as_secs_f32());
+        }
+        self.compose();
+    }
+
+    /// The frame composed by the last `tick`
+    pub fn frame(&self) -> &Frame {
+        &self.frame
+    }
+
+    /// Advance the simulation state by `dt` seconds
+    fn step(&mut self, dt: f32) {
+        let rng = &mut self.rng;
+        let rows = self.rows;
+        self.steps += 1;
+
+        // Age stuck characters and clean up old ones
+        if self.config.stuck_enabled() {
+            self.sticky_chars.retain(|_, (_, age)| {
+                *age += dt;
+                *age < STICKY_LIFETIME
+            });
+        }
+
+        // Start fed drops in random free columns as soon as they arrive
+        if !self.pending.is_empty() {
+            let stride = self.config.column_stride();
+            let mut free: Vec<usize> = (0..self.drops.len()).filter(|&i| self.drops[i].is_none()).collect();
+            free.shuffle(rng);
+            for lane in free {
+                let Some(pending) = self.pending.pop_front() else {
+                    break;
+                };
+                let drop = MatrixDrop::with_text(lane as u16 * stride, &pending.text, &self.config, rng);
+                self.drops[lane] = Some(drop.with_tint(pending.tint));
+            }
+        }
+
+        // Spawn new drops periodically
+        self.spawn_timer += dt;
+        if !self.config.feed_only() && self.spawn_timer > SPAWN_CHECK_INTERVAL {
+            let stride = self.config.column_stride();
+            for (lane, drop_slot) in self.drops.iter_mut().enumerate() {
+                if drop_slot.is_none() && rng.random::<f32>() < self.config.new_drop_probability() {
+                    *drop_slot = Some(MatrixDrop::new(lane as u16 * stride, rows, &self.config, rng));
+                }
+            }
+            self.spawn_timer = 0.0;
+        }
+
+        // Advance drops
+        for slot in self.drops.iter_mut() {
+            let Some(drop) = slot else {
+                continue;
+            };
+            let should_reset = drop.update(rows, dt, &self.config, rng);
+
+            // Check if drop should leave a stuck character before resetting
+            if self.config.stuck_enabled() && should_reset
+                && let Some((x, y, ch)) = drop.should_leave_sticky(rows, self.config.stuck_probability(), rng)
+            {
+                self.sticky_chars.insert((x, y), (ch, 0.0));
+            }
+
+            // Fed drops fall once; the column waits for more input
+            if should_reset && self.config.feed_only() {
+                *slot = None;
+            } else if should_reset {
+                *drop = MatrixDrop::new(drop.x(), rows, &self.config, rng);
+            }
+        }
+
+        // Drops passing over stuck characters wipe them out
+        if !self.sticky_chars.is_empty() {
+            let drops = &self.drops;
+            self.sticky_chars
+                .retain(|&(x, y), _| !drops.iter().flatten().any(|drop| drop.covers(x, y)));
+        }
+    }
+
+    /// Compose the frame grid from the current state
+    fn compose(&mut self) {
+        // Flicker and glitches come from a generator derived from the step
+        // count, so a frame doesn't depend on how the ticks were chunked
+        let mut effects_rng = MatrixRng::seed_from_u64(
+            self.seed ^ self.steps.wrapping_mul(EFFECTS_SEED_MIX),
+        );
+        self.frame.clear();
+
+        // Get stuck character color
+        let stuck_color = match (self.config.sticky_color(), self.config.gradient()) {
+            (Some(color), _) => fit_color(color, self.config.rgb_fade()),
+            (None, Some(gradient)) => {
+                rgb_color(gradient.sample(STICKY_GRADIENT_POSITION), self.config.rgb_fade())
+            }
+            (None, None) => self.config.color_scheme().get_colors().2,
+        };
+
+        // Draw stuck characters first (so drops can overwrite them)
+        if self.config.stuck_enabled() {
+            for (&(x, y), &(ch, _)) in self.sticky_chars.iter() {
+                self.frame.set(x, y, FrameCell::new(ch, stuck_color, STICKY_INTENSITY));
+            }
+        }
+
+        // Draw drops, moved on by the time since the last step
+        let ahead = self.accumulator.as_secs_f32();
+        let time = self.steps as f32 * SIMULATION_STEP.as_secs_f32() + ahead;
+        for drop in self.drops.iter().flatten() {
+            drop.render(&mut self.frame, &self.config, time, ahead, &mut effects_rng);
+        }
+
+        if self.config.narrow() {
+            self.frame.make_narrow();
+        }
+        if self.config.mono() {
+            self.frame.make_monochrome();
+        }
+    }
+
+    /// Draw the last composed frame as a fresh renderer frame
+    ///
+    /// The renderer is cleared first; call `Renderer::flush` afterwards to
+    /// present the frame.
+    pub fn render(&self, renderer: &mut impl Renderer) {
+        renderer.clear();
+        for (x, y, cell) in self.frame.iter() {
+            renderer.put_cell(x, y, (*cell).into());
+        }
+    }
+}
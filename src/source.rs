@@ -0,0 +1,307 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use rand::prelude::IndexedRandom;
+use rand::Rng;
+
+use crate::charset::{char_width, is_drawable};
+
+/// Files larger than this are skipped, in bytes
+pub const MAX_SOURCE_FILE_SIZE: u64 = 1 << 20;
+/// Default limit on the text loaded from all files together, in bytes
+pub const MAX_SOURCE_TOTAL_SIZE: u64 = 16 << 20;
+/// Most files a directory walk collects
+const MAX_SOURCE_FILES: usize = 10_000;
+/// Build output and dependency directories skipped when walking
+const SKIPPED_DIRS: &[&str] = &["target", "node_modules", "build", "dist", "__pycache__", "venv"];
+/// How much of a file is checked for NUL bytes to detect binaries
+const BINARY_SNIFF_LEN: usize = 8000;
+
+/// How whitespace in source files is drawn
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Whitespace {
+    /// Every space, tab and line break shows as a gap
+    Keep,
+    /// Runs of whitespace, including indentation, become a single gap
+    #[default]
+    Collapse,
+    /// Whitespace is removed, packing the code together
+    Strip,
+}
+
+impl Whitespace {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "keep" => Ok(Self::Keep),
+            "collapse" => Ok(Self::Collapse),
+            "strip" => Ok(Self::Strip),
+            other => Err(format!("unknown whitespace mode '{other}', expected keep, collapse or strip")),
+        }
+    }
+}
+
+/// One file's text, prepared for drawing
+#[derive(Clone, Debug)]
+struct SourceFile {
+    chars: Vec<char>,
+    /// Where each non-blank line starts in `chars`
+    line_starts: Vec<usize>,
+}
+
+/// Text that drops stream through, e.g. a codebase
+///
+/// Each drop takes a contiguous run of characters starting at a random line
+/// of a random file, continuing into the following lines if the line is
+/// short. Cloning is cheap; the text is shared.
+#[derive(Clone, Debug)]
+pub struct SourceText {
+    files: Arc<Vec<SourceFile>>,
+    width: usize,
+}
+
+impl SourceText {
+    /// Build from in-memory texts, skipping any without drawable characters
+    pub fn from_texts<S: AsRef<str>>(
+        texts: impl IntoIterator<Item = S>,
+        whitespace: Whitespace,
+    ) -> Result<Self, String> {
+        let files: Vec<SourceFile> = texts
+            .into_iter()
+            .filter_map(|text| prepare(text.as_ref(), whitespace))
+            .collect();
+        if files.is_empty() {
+            return Err("no text to draw".to_string());
+        }
+        let width = files
+            .iter()
+            .flat_map(|file| file.chars.iter())
+            .map(|&c| char_width(c))
+            .max()
+            .unwrap_or(1);
+        Ok(Self { files: Arc::new(files), width })
+    }
+
+    /// Take `len` characters starting at a random line of a random file,
+    /// wrapping around to the start of the file if it runs out
+    pub fn sample(&self, len: usize, rng: &mut impl Rng) -> Vec<char> {
+        let file = self.files.choose(rng).expect("source text is never empty");
+        let start = *file.line_starts.choose(rng).unwrap_or(&0);
+        file.chars.iter().cycle().skip(start).take(len).copied().collect()
+    }
+
+    /// Number of files with text
+    pub fn files(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Cells the widest character takes up
+    pub fn width(&self) -> usize {
+        self.width
+    }
+}
+
+/// Collects the text files under a set of paths into a `SourceText`
+///
+/// Directories are walked recursively, skipping hidden entries such as
+/// `.git` and build directories such as `target` and `node_modules`. Binary
+/// files, files that aren't UTF-8 and files over `MAX_SOURCE_FILE_SIZE` are
+/// skipped, and loading stops once the total size limit is reached.
+#[derive(Clone, Debug)]
+pub struct SourceLoader {
+    globs: Vec<String>,
+    whitespace: Whitespace,
+    max_total_size: u64,
+}
+
+impl Default for SourceLoader {
+    fn default() -> Self {
+        Self { globs: Vec::new(), whitespace: Whitespace::default(), max_total_size: MAX_SOURCE_TOTAL_SIZE }
+    }
+}
+
+impl SourceLoader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only use files matching one of these patterns
+    ///
+    /// `*` matches within a path component, `**` across components and `?`
+    /// a single character. Patterns without a `/` match the file name, others
+    /// the path relative to the directory being walked.
+    pub fn with_globs(mut self, globs: Vec<String>) -> Self {
+        self.globs = globs;
+        self
+    }
+
+    pub fn with_whitespace(mut self, whitespace: Whitespace) -> Self {
+        self.whitespace = whitespace;
+        self
+    }
+
+    /// Stop loading files once their text adds up to this many bytes
+    pub fn with_max_total_size(mut self, bytes: u64) -> Self {
+        self.max_total_size = bytes;
+        self
+    }
+
+    pub fn load(&self, paths: &[PathBuf]) -> Result<SourceText, String> {
+        let mut files = Vec::new();
+        for path in paths {
+            let metadata = fs::metadata(path).map_err(|err| format!("{}: {err}", path.display()))?;
+            if metadata.is_dir() {
+                self.walk(path, path, &mut files);
+            } else {
+                // Files named explicitly are used whatever the globs say
+                files.push(path.clone());
+            }
+        }
+
+        let mut total = 0;
+        let texts: Vec<String> = files
+            .iter()
+            .filter_map(|path| read_text(path))
+            .take_while(|text| {
+                total += text.len() as u64;
+                total <= self.max_total_size
+            })
+            .collect();
+        SourceText::from_texts(texts, self.whitespace).map_err(|_| {
+            let paths: Vec<String> = paths.iter().map(|path| path.display().to_string()).collect();
+            format!("no readable text files in {}", paths.join(", "))
+        })
+    }
+
+    /// Collect matching files under `dir`, in a stable order
+    fn walk(&self, root: &Path, dir: &Path, files: &mut Vec<PathBuf>) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        let mut entries: Vec<_> = entries.filter_map(Result::ok).collect();
+        entries.sort_by_key(|entry| entry.file_name());
+
+        for entry in entries {
+            if files.len() >= MAX_SOURCE_FILES {
+                return;
+            }
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if name.starts_with('.') {
+                continue;
+            }
+            // Symlinked directories are not followed, to avoid loops
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            let path = entry.path();
+            if file_type.is_dir() {
+                if !SKIPPED_DIRS.contains(&name.as_str()) {
+                    self.walk(root, &path, files);
+                }
+            } else if self.matches(root, &path) {
+                files.push(path);
+            }
+        }
+    }
+
+    fn matches(&self, root: &Path, path: &Path) -> bool {
+        if self.globs.is_empty() {
+            return true;
+        }
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let relative = path.strip_prefix(root).unwrap_or(path);
+        let relative: Vec<String> = relative
+            .components()
+            .map(|part| part.as_os_str().to_string_lossy().into_owned())
+            .collect();
+        let relative = relative.join("/");
+        self.globs.iter().any(|glob| {
+            let target = if glob.contains('/') { relative.as_str() } else { &name };
+            glob_match(glob, target)
+        })
+    }
+}
+
+/// Read a file as text, or `None` for binaries and unreadable files
+fn read_text(path: &Path) -> Option<String> {
+    let metadata = fs::metadata(path).ok()?;
+    if metadata.len() > MAX_SOURCE_FILE_SIZE {
+        return None;
+    }
+    let bytes = fs::read(path).ok()?;
+    if bytes[..bytes.len().min(BINARY_SNIFF_LEN)].contains(&0) {
+        return None;
+    }
+    String::from_utf8(bytes).ok()
+}
+
+/// Turn a file's text into drawable characters and line starts
+fn prepare(text: &str, whitespace: Whitespace) -> Option<SourceFile> {
+    let mut chars = Vec::new();
+    let mut line_starts = Vec::new();
+    for line in text.lines() {
+        let start = chars.len();
+        let mut gap = false;
+        for c in line.chars() {
+            if c.is_whitespace() {
+                gap = true;
+                if whitespace == Whitespace::Keep {
+                    chars.push(' ');
+                }
+                continue;
+            }
+            if !is_drawable(c) {
+                continue;
+            }
+            // Collapsed whitespace becomes one gap between words, not before the first
+            if gap && whitespace == Whitespace::Collapse && chars.len() > start {
+                chars.push(' ');
+            }
+            gap = false;
+            chars.push(c);
+        }
+
+        let blank = chars[start..].iter().all(|&c| c == ' ');
+        if blank && whitespace != Whitespace::Keep {
+            chars.truncate(start);
+            continue;
+        }
+        if !blank {
+            line_starts.push(start);
+        }
+        // Line breaks separate the end of one line from the next
+        if whitespace != Whitespace::Strip {
+            chars.push(' ');
+        }
+    }
+    (!line_starts.is_empty()).then_some(SourceFile { chars, line_starts })
+}
+
+/// Match `text` against a glob with `*`, `**` and `?`
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    glob_match_from(&pattern, &text)
+}
+
+fn glob_match_from(pattern: &[char], text: &[char]) -> bool {
+    match pattern {
+        [] => text.is_empty(),
+        ['*', '*', rest @ ..] => {
+            // `**/` also matches no directories at all
+            if let ['/', after @ ..] = rest
+                && glob_match_from(after, text)
+            {
+                return true;
+            }
+            (0..=text.len()).any(|i| glob_match_from(rest, &text[i..]))
+        }
+        ['*', rest @ ..] => {
+            let limit = text.iter().position(|&c| c == '/').unwrap_or(text.len());
+            (0..=limit).any(|i| glob_match_from(rest, &text[i..]))
+        }
+        ['?', rest @ ..] => matches!(text, [c, tail @ ..] if *c != '/' && glob_match_from(rest, tail)),
+        [p, rest @ ..] => matches!(text, [c, tail @ ..] if c == p && glob_match_from(rest, tail)),
+    }
+}
+
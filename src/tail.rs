@@ -0,0 +1,90 @@
+use std::fs::{self, File, Metadata};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+/// How often a followed file is checked for new data, in the default setup
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Follows a growing file like `tail -F`, as a reader that never ends
+///
+/// Reading starts at the end of the file and waits for more to be written.
+/// When the file is truncated, reading starts over from its beginning; when
+/// it is replaced, e.g. by log rotation, the new file is opened once the old
+/// one has been read to the end. Hand it to `Feed::from_reader` to rain a log.
+pub struct TailReader {
+    path: PathBuf,
+    file: File,
+    position: u64,
+    poll_interval: Duration,
+}
+
+impl TailReader {
+    /// Open `path`, skipping everything already in it
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let mut file = File::open(path)?;
+        let position = file.seek(SeekFrom::End(0))?;
+        Ok(Self { path: path.to_path_buf(), file, position, poll_interval: DEFAULT_POLL_INTERVAL })
+    }
+
+    /// How long to wait between checks when there is nothing new to read
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Start over if the file shrank or was replaced; returns whether it did
+    fn reopen_if_changed(&mut self) -> io::Result<bool> {
+        // Between a rotation and the new file appearing there is nothing to do
+        let Ok(current) = fs::metadata(&self.path) else {
+            return Ok(false);
+        };
+        let open = self.file.metadata()?;
+        if !is_same_file(&current, &open) {
+            if let Ok(file) = File::open(&self.path) {
+                self.file = file;
+                self.position = 0;
+                return Ok(true);
+            }
+            return Ok(false);
+        }
+        if open.len() < self.position {
+            self.position = self.file.seek(SeekFrom::Start(0))?;
+            return Ok(true);
+        }
+        Ok(false)
+    }
+}
+
+impl Read for TailReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            let n = self.file.read(buf)?;
+            if n > 0 || buf.is_empty() {
+                self.position += n as u64;
+                return Ok(n);
+            }
+            if !self.reopen_if_changed()? {
+                thread::sleep(self.poll_interval);
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+fn is_same_file(a: &Metadata, b: &Metadata) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    a.dev() == b.dev() && a.ino() == b.ino()
+}
+
+/// Without inode numbers a replaced file can only be told apart by size,
+/// which the truncation check already covers
+#[cfg(not(unix))]
+fn is_same_file(_a: &Metadata, _b: &Metadata) -> bool {
+    true
+}
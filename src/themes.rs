@@ -0,0 +1,177 @@
+use crossterm::style::Color;
+
+use crate::colors::MatrixColorScheme;
+use crate::config::MatrixConfig;
+use crate::gradient::{Easing, Gradient, GradientStop};
+
+/// A named color preset for the whole effect
+#[derive(Clone, Copy, Debug)]
+pub struct Theme {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Color of the leading character of each drop
+    pub head: (u8, u8, u8),
+    /// Trail gradient as (position, color) stops, from head to tail
+    pub trail: &'static [(f32, (u8, u8, u8))],
+    pub easing: Easing,
+    pub sticky: (u8, u8, u8),
+    pub glitch: (u8, u8, u8),
+}
+
+impl Theme {
+    /// The trail gradient as a `Gradient`
+    pub fn gradient(&self) -> Gradient {
+        let stops = self
+            .trail
+            .iter()
+            .map(|&(position, rgb)| GradientStop { position, rgb })
+            .collect();
+        Gradient::new(stops)
+            .expect("built-in themes have at least one stop")
+            .with_easing(self.easing)
+    }
+
+    /// Apply every color of the theme to a config
+    pub fn apply(&self, config: MatrixConfig) -> MatrixConfig {
+        let gradient = self.gradient();
+        let (r, g, b) = gradient.sample(0.3);
+        config
+            .with_color_scheme(MatrixColorScheme::Custom(Color::Rgb { r, g, b }))
+            .with_gradient(Some(gradient))
+            .with_head_color(Some(rgb(self.head)))
+            .with_sticky_color(Some(rgb(self.sticky)))
+            .with_glitch_color(Some(rgb(self.glitch)))
+    }
+}
+
+fn rgb((r, g, b): (u8, u8, u8)) -> Color {
+    Color::Rgb { r, g, b }
+}
+
+/// Look up a built-in theme by name, ignoring case
+pub fn find_theme(name: &str) -> Option<&'static Theme> {
+    let name = name.trim().to_ascii_lowercase().replace('_', "-");
+    THEMES.iter().find(|theme| theme.name == name)
+}
+
+/// Look up a built-in theme, with an error listing the valid names
+pub fn parse_theme(name: &str) -> Result<&'static Theme, String> {
+    find_theme(name).ok_or_else(|| {
+        let names: Vec<&str> = THEMES.iter().map(|theme| theme.name).collect();
+        format!("unknown theme '{name}', expected one of: {}", names.join(", "))
+    })
+}
+
+// ==== Built-in Themes ====
+pub const THEMES: &[Theme] = &[
+    Theme {
+        name: "matrix",
+        description: "Phosphor green from the film, white-hot heads",
+        head: (220, 255, 220),
+        trail: &[
+            (0.0, (180, 255, 180)),
+            (0.15, (0, 255, 65)),
+            (0.5, (0, 143, 17)),
+            (1.0, (0, 40, 0)),
+        ],
+        easing: Easing::EaseOut,
+        sticky: (0, 143, 17),
+        glitch: (200, 255, 200),
+    },
+    Theme {
+        name: "amber",
+        description: "Amber monochrome terminal",
+        head: (255, 236, 179),
+        trail: &[
+            (0.0, (255, 200, 80)),
+            (0.2, (255, 176, 0)),
+            (0.6, (170, 100, 0)),
+            (1.0, (50, 25, 0)),
+        ],
+        easing: Easing::Linear,
+        sticky: (204, 122, 0),
+        glitch: (255, 243, 214),
+    },
+    Theme {
+        name: "ice-blue",
+        description: "Ice blue fading into deep water",
+        head: (232, 251, 255),
+        trail: &[
+            (0.0, (155, 231, 255)),
+            (0.3, (57, 160, 237)),
+            (0.7, (11, 61, 145)),
+            (1.0, (2, 12, 31)),
+        ],
+        easing: Easing::Linear,
+        sticky: (57, 160, 237),
+        glitch: (255, 255, 255),
+    },
+    Theme {
+        name: "blood-red",
+        description: "Blood red on black",
+        head: (255, 214, 214),
+        trail: &[
+            (0.0, (255, 26, 26)),
+            (0.4, (179, 0, 0)),
+            (0.8, (77, 0, 0)),
+            (1.0, (26, 0, 0)),
+        ],
+        easing: Easing::EaseIn,
+        sticky: (139, 0, 0),
+        glitch: (255, 153, 153),
+    },
+    Theme {
+        name: "synthwave",
+        description: "Hot pink through violet into a midnight sky",
+        head: (255, 240, 255),
+        trail: &[
+            (0.0, (255, 43, 214)),
+            (0.35, (157, 78, 221)),
+            (0.7, (58, 12, 163)),
+            (1.0, (16, 0, 43)),
+        ],
+        easing: Easing::Linear,
+        sticky: (247, 37, 133),
+        glitch: (0, 245, 255),
+    },
+    Theme {
+        name: "vaporwave",
+        description: "Pastel pink, cyan and mint",
+        head: (255, 255, 255),
+        trail: &[
+            (0.0, (255, 113, 206)),
+            (0.35, (1, 205, 254)),
+            (0.7, (5, 255, 161)),
+            (1.0, (185, 103, 255)),
+        ],
+        easing: Easing::EaseInOut,
+        sticky: (255, 251, 150),
+        glitch: (1, 205, 254),
+    },
+    Theme {
+        name: "monochrome",
+        description: "Shades of grey",
+        head: (255, 255, 255),
+        trail: &[
+            (0.0, (208, 208, 208)),
+            (0.5, (128, 128, 128)),
+            (1.0, (48, 48, 48)),
+        ],
+        easing: Easing::Linear,
+        sticky: (128, 128, 128),
+        glitch: (255, 255, 255),
+    },
+    Theme {
+        name: "colorblind-safe",
+        description: "Blue trails with orange accents, safe for common color vision deficiencies",
+        head: (255, 255, 255),
+        trail: &[
+            (0.0, (86, 180, 233)),
+            (0.5, (0, 114, 178)),
+            (1.0, (0, 43, 69)),
+        ],
+        easing: Easing::Linear,
+        sticky: (230, 159, 0),
+        glitch: (240, 228, 66),
+    },
+];
@@ -0,0 +1,148 @@
+//! ratatui widget for the Matrix rain (enabled with the `ratatui` feature)
+
+use crossterm::style::Color;
+use ratatui::{buffer::Buffer, layout::Rect, style, widgets::StatefulWidget};
+use std::time::{Duration, Instant};
+
+use crate::config::MatrixConfig;
+use crate::simulation::MatrixSimulation;
+
+/// State kept between renders of a [`MatrixRainWidget`]
+///
+/// The simulation is created on first render and resized whenever the
+/// widget's area changes size.
+pub struct MatrixRainState {
+    config: MatrixConfig,
+    simulation: Option<MatrixSimulation>,
+    last_render: Option<Instant>,
+}
+
+impl MatrixRainState {
+    pub fn new(config: MatrixConfig) -> Self {
+        Self {
+            config,
+            simulation: None,
+            last_render: None,
+        }
+    }
+
+    /// The running simulation, once the widget has been rendered
+    pub fn simulation(&self) -> Option<&MatrixSimulation> {
+        self.simulation.as_ref()
+    }
+
+    /// Advance the rain by `dt`, for use with [`MatrixRainWidget::manual`]
+    pub fn tick(&mut self, dt: Duration) {
+        if let Some(simulation) = &mut self.simulation {
+            simulation.tick(dt);
+        }
+    }
+
+    /// Make sure the simulation matches the area, returning true if it was (re)created
+    fn fit(&mut self, cols: u16, rows: u16) -> bool {
+        match &mut self.simulation {
+            Some(simulation) if simulation.size() == (cols, rows) => false,
+            Some(simulation) => {
+                simulation.resize(cols, rows);
+                true
+            }
+            None => {
+                self.simulation = Some(MatrixSimulation::new(self.config.clone(), cols, rows));
+                true
+            }
+        }
+    }
+}
+
+impl Default for MatrixRainState {
+    fn default() -> Self {
+        Self::new(MatrixConfig::default())
+    }
+}
+
+/// Renders Matrix rain into a ratatui buffer area
+///
+/// Empty cells are left untouched, so the rain can sit behind other widgets
+/// when it is rendered first. By default the rain advances by the wall-clock
+/// time since the previous render.
+#[derive(Clone, Copy, Debug)]
+pub struct MatrixRainWidget {
+    auto_tick: bool,
+}
+
+impl MatrixRainWidget {
+    pub fn new() -> Self {
+        Self { auto_tick: true }
+    }
+
+    /// Only draw the current state; advance it with [`MatrixRainState::tick`]
+    pub fn manual() -> Self {
+        Self { auto_tick: false }
+    }
+}
+
+impl Default for MatrixRainWidget {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StatefulWidget for MatrixRainWidget {
+    type State = MatrixRainState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let area = area.intersection(buf.area);
+        if area.is_empty() {
+            return;
+        }
+
+        let resized = state.fit(area.width, area.height);
+        let now = Instant::now();
+        let elapsed = state.last_render.map_or(Duration::ZERO, |last| now.duration_since(last));
+        state.last_render = Some(now);
+
+        let Some(simulation) = &mut state.simulation else {
+            return;
+        };
+        if self.auto_tick || resized {
+            simulation.tick(elapsed);
+        }
+
+        for (x, y, cell) in simulation.frame().iter() {
+            if let Some(target) = buf.cell_mut((area.x + x, area.y + y)) {
+                let mut modifier = style::Modifier::empty();
+                modifier.set(style::Modifier::BOLD, cell.attrs.bold);
+                modifier.set(style::Modifier::DIM, cell.attrs.dim);
+                target
+                    .set_char(cell.glyph)
+                    .set_fg(to_ratatui_color(cell.color))
+                    .set_style(style::Style::new().add_modifier(modifier));
+            }
+        }
+    }
+}
+
+/// Convert a crossterm color to the equivalent ratatui color
+fn to_ratatui_color(color: Color) -> style::Color {
+    match color {
+        Color::Reset => style::Color::Reset,
+        Color::Black => style::Color::Black,
+        Color::DarkRed => style::Color::Red,
+        Color::DarkGreen => style::Color::Green,
+        Color::DarkYellow => style::Color::Yellow,
+        Color::DarkBlue => style::Color::Blue,
+        Color::DarkMagenta => style::Color::Magenta,
+        Color::DarkCyan => style::Color::Cyan,
+        Color::Grey => style::Color::Gray,
+        Color::DarkGrey => style::Color::DarkGray,
+        Color::Red => style::Color::LightRed,
+        Color::Green => style::Color::LightGreen,
+        Color::Yellow => style::Color::LightYellow,
+        Color::Blue => style::Color::LightBlue,
+        Color::Magenta => style::Color::LightMagenta,
+        Color::Cyan => style::Color::LightCyan,
+        Color::White => style::Color::White,
+        Color::Rgb { r, g, b } => style::Color::Rgb(r, g, b),
+        Color::AnsiValue(v) => style::Color::Indexed(v),
+    }
+}
@@ -0,0 +1,79 @@
+use std::fs;
+use std::path::Path;
+
+use rand::prelude::IndexedRandom;
+use rand::Rng;
+
+use crate::charset::{char_width, is_drawable};
+
+/// Words or phrases that drops spell out, top to bottom
+///
+/// Each drop picks one entry; its characters sit steady just behind the head
+/// while the rest of the trail keeps changing.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Wordlist {
+    words: Vec<Vec<char>>,
+}
+
+impl Wordlist {
+    /// Build a wordlist, dropping entries with nothing drawable in them
+    ///
+    /// Control and zero-width characters are removed; spaces inside a
+    /// phrase are kept and show as gaps.
+    pub fn new<S: AsRef<str>>(words: impl IntoIterator<Item = S>) -> Result<Self, String> {
+        let words: Vec<Vec<char>> = words
+            .into_iter()
+            .map(|word| {
+                word.as_ref()
+                    .trim()
+                    .chars()
+                    .filter(|&c| c == ' ' || is_drawable(c))
+                    .collect::<Vec<char>>()
+            })
+            .filter(|word| word.iter().any(|&c| c != ' '))
+            .collect();
+        if words.is_empty() {
+            return Err("the wordlist has no words".to_string());
+        }
+        Ok(Self { words })
+    }
+
+    /// Parse an inline comma-separated list, e.g. `--words "neo,trinity,morpheus"`
+    pub fn parse(list: &str) -> Result<Self, String> {
+        Self::new(list.split(','))
+    }
+
+    /// Read a wordlist file with one word or phrase per line
+    ///
+    /// Blank lines and lines starting with `#` are skipped.
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let contents = fs::read_to_string(path).map_err(|err| format!("{}: {err}", path.display()))?;
+        Self::new(contents.lines().filter(|line| !line.trim_start().starts_with('#')))
+            .map_err(|err| format!("{}: {err}", path.display()))
+    }
+
+    /// Combine two wordlists
+    pub fn merge(mut self, other: Wordlist) -> Self {
+        self.words.extend(other.words);
+        self
+    }
+
+    /// Pick a random word
+    pub fn choose(&self, rng: &mut impl Rng) -> &[char] {
+        self.words.choose(rng).expect("wordlist is never empty")
+    }
+
+    pub fn words(&self) -> &[Vec<char>] {
+        &self.words
+    }
+
+    /// Cells the widest character in any word takes up
+    pub fn width(&self) -> usize {
+        self.words.iter().flatten().map(|&c| char_width(c)).max().unwrap_or(1)
+    }
+
+    /// Length of the longest word, in characters
+    pub fn longest(&self) -> usize {
+        self.words.iter().map(Vec::len).max().unwrap_or(0)
+    }
+}
@@ -0,0 +1,102 @@
+use crossterm::style::Color;
+use make_it_rain::{
+    capability::{nearest_ansi_16, parse_osc11, terminfo_colors},
+    Cell, ColorDepth, ColorDepthChoice, CrosstermRenderer, Renderer,
+};
+
+/// Build a minimal legacy-format terminfo entry with the given numbers
+fn terminfo(names: &str, bools: usize, numbers: &[i16]) -> Vec<u8> {
+    let mut data = Vec::new();
+    for header in [0o432, names.len() as u16 + 1, bools as u16, numbers.len() as u16, 0, 0] {
+        data.extend_from_slice(&header.to_le_bytes());
+    }
+    data.extend_from_slice(names.as_bytes());
+    data.push(0);
+    data.extend(std::iter::repeat_n(1, bools));
+    if data.len() % 2 == 1 {
+        data.push(0);
+    }
+    for n in numbers {
+        data.extend_from_slice(&n.to_le_bytes());
+    }
+    data
+}
+
+#[test]
+fn colorterm_wins() {
+    assert_eq!(ColorDepth::from_env(Some("truecolor"), Some("xterm"), Some(8)), ColorDepth::TrueColor);
+    assert_eq!(ColorDepth::from_env(Some("24bit"), None, None), ColorDepth::TrueColor);
+}
+
+#[test]
+fn detects_from_term_and_terminfo() {
+    let depth = |term, colors| ColorDepth::from_env(None, Some(term), colors);
+    assert_eq!(depth("dumb", None), ColorDepth::Mono);
+    assert_eq!(depth("xterm-direct", None), ColorDepth::TrueColor);
+    assert_eq!(depth("xterm-256color", Some(256)), ColorDepth::Ansi256);
+    assert_eq!(depth("linux", Some(8)), ColorDepth::Ansi16);
+    assert_eq!(depth("vt100", Some(0)), ColorDepth::Mono);
+    assert_eq!(depth("foot", Some(1 << 24)), ColorDepth::TrueColor);
+    assert_eq!(depth("screen-256color", None), ColorDepth::Ansi256);
+    assert_eq!(depth("screen", None), ColorDepth::Ansi16);
+}
+
+#[test]
+fn reads_colors_from_terminfo() {
+    let mut numbers = vec![-1; 15];
+    numbers[13] = 256;
+    assert_eq!(terminfo_colors(&terminfo("xterm-256color|xterm with 256 colors", 3, &numbers)), Some(256));
+    assert_eq!(terminfo_colors(&terminfo("odd", 2, &numbers)), Some(256));
+
+    numbers[13] = -1;
+    assert_eq!(terminfo_colors(&terminfo("vt100", 3, &numbers)), None);
+    assert_eq!(terminfo_colors(&terminfo("short", 3, &[80, 24])), None);
+    assert_eq!(terminfo_colors(b"not terminfo"), None);
+}
+
+#[test]
+fn parses_overrides() {
+    assert_eq!(ColorDepthChoice::parse("auto"), Ok(ColorDepthChoice::Auto));
+    assert_eq!(ColorDepthChoice::parse("Auto").unwrap().depth(), None);
+    assert_eq!(ColorDepthChoice::parse("TrueColor"), Ok(ColorDepthChoice::Fixed(ColorDepth::TrueColor)));
+    assert_eq!(ColorDepth::parse("256"), Ok(ColorDepth::Ansi256));
+    assert_eq!(ColorDepth::parse("16"), Ok(ColorDepth::Ansi16));
+    assert_eq!(ColorDepthChoice::parse("mono").unwrap().depth(), Some(ColorDepth::Mono));
+    assert!(ColorDepth::parse("auto").is_err());
+    assert!(ColorDepthChoice::parse("lots").unwrap_err().contains("auto"));
+}
+
+#[test]
+fn quantizes_to_each_depth() {
+    let green = Color::Rgb { r: 0, g: 250, b: 10 };
+    assert_eq!(ColorDepth::TrueColor.quantize(green), green);
+    assert_eq!(ColorDepth::Ansi256.quantize(green), Color::AnsiValue(46));
+    assert_eq!(ColorDepth::Ansi16.quantize(green), Color::Green);
+    assert_eq!(ColorDepth::Ansi16.quantize(Color::AnsiValue(196)), Color::Red);
+    assert_eq!(ColorDepth::Ansi16.quantize(Color::DarkGreen), Color::DarkGreen);
+    assert_eq!(ColorDepth::Mono.quantize(green), Color::Reset);
+    assert_eq!(nearest_ansi_16((250, 250, 250)), 15);
+}
+
+#[test]
+fn renderer_downsamples_truecolor() {
+    let mut renderer = CrosstermRenderer::new(Vec::new(), 4, 1).with_color_depth(ColorDepth::Ansi16);
+    renderer.put_cell(0, 0, Cell::new('x', Color::Rgb { r: 10, g: 200, b: 30 }));
+    renderer.flush().unwrap();
+
+    let output = String::from_utf8_lossy(renderer.writer()).into_owned();
+    assert!(output.contains('x'));
+    assert!(!output.contains("38;2;"), "truecolor escape in 16-color output: {output:?}");
+    assert!(!output.contains("38;5;"), "256-color escape in 16-color output: {output:?}");
+}
+
+#[test]
+fn parses_background_replies() {
+    assert_eq!(parse_osc11("\x1b]11;rgb:ffff/ffff/ffff\x07"), Some((255, 255, 255)));
+    assert_eq!(parse_osc11("\x1b]11;rgb:fdfd/f6f6/e3e3\x1b\\"), Some((253, 246, 227)));
+    assert_eq!(parse_osc11("\x1b]11;rgb:00/80/ff\x07"), Some((0, 128, 255)));
+    assert_eq!(parse_osc11("\x1b]11;rgb:f/0/8\x07"), Some((255, 0, 136)));
+    assert_eq!(parse_osc11(""), None);
+    assert_eq!(parse_osc11("\x1b]11;rgb:ffff/ffff\x07"), None);
+    assert_eq!(parse_osc11("\x1b]11;rgb:zz/00/00\x07"), None);
+}
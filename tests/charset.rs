@@ -0,0 +1,92 @@
+use crossterm::style::Color;
+use make_it_rain::{
+    charset::{is_reliably_narrow, narrow_fallback},
+    Cell, Charset, CrosstermRenderer, MatrixRng, MemoryRenderer, Renderer,
+};
+use rand::SeedableRng;
+
+fn lookup(name: &str) -> Result<Vec<char>, String> {
+    match name {
+        "binary" => Ok(vec!['0', '1']),
+        "abc" => Ok(vec!['a', 'b', 'c']),
+        "x" => Ok(vec!['x']),
+        other => Err(format!("unknown palette '{other}'")),
+    }
+}
+
+#[test]
+fn literal_chars_keep_unicode_and_skip_whitespace() {
+    let charset = Charset::from_chars("01 ｱ\tΩ🙂").unwrap();
+    assert_eq!(charset.chars().collect::<String>(), "01ｱΩ🙂");
+    assert!(Charset::from_chars(" \n").is_err());
+}
+
+#[test]
+fn plus_merges_palettes_into_one_group() {
+    let charset = Charset::parse("binary+abc", lookup).unwrap();
+    assert_eq!(charset.groups(), &[(vec!['0', '1', 'a', 'b', 'c'], 1)]);
+    assert_eq!(charset.len(), 5);
+}
+
+#[test]
+fn commas_make_weighted_groups() {
+    let charset = Charset::parse("abc:3, binary+x", lookup).unwrap();
+    assert_eq!(
+        charset.groups(),
+        &[(vec!['a', 'b', 'c'], 3), (vec!['0', '1', 'x'], 1)]
+    );
+}
+
+#[test]
+fn rejects_bad_compositions() {
+    assert!(Charset::parse("nope", lookup).unwrap_err().contains("nope"));
+    assert!(Charset::parse("abc:0", lookup).is_err());
+    assert!(Charset::parse("abc:lots", lookup).is_err());
+    assert!(Charset::parse("abc+", lookup).is_err());
+    assert!(Charset::parse("", lookup).is_err());
+}
+
+#[test]
+fn weights_set_how_often_groups_are_picked() {
+    let charset = Charset::parse("x:3,binary:1", lookup).unwrap();
+    let mut rng = MatrixRng::seed_from_u64(1);
+    let picks = 8000;
+    let xs = (0..picks).filter(|_| charset.choose(&mut rng) == 'x').count();
+    let share = xs as f64 / picks as f64;
+    assert!((share - 0.75).abs() < 0.03, "x picked {share:.3} of the time");
+}
+
+#[test]
+fn narrow_fallbacks_are_one_cell_everywhere() {
+    assert_eq!(narrow_fallback('A'), 'A');
+    assert_eq!(narrow_fallback('Ａ'), 'A');
+    assert_eq!(narrow_fallback('─'), '-');
+    assert_eq!(narrow_fallback('║'), '|');
+    assert_eq!(narrow_fallback('╬'), '+');
+    assert_eq!(narrow_fallback('▓'), '#');
+    // Unmatched glyphs always get the same stand-in
+    assert_eq!(narrow_fallback('日'), narrow_fallback('日'));
+    for c in ['ｱ', 'Ω', 'Ж', '日', '😀', '가', '⠿'] {
+        let fallback = narrow_fallback(c);
+        assert!(is_reliably_narrow(fallback), "{c} fell back to {fallback}");
+    }
+}
+
+#[test]
+fn wide_glyphs_cover_the_next_cell() {
+    let mut renderer = CrosstermRenderer::new(Vec::new(), 4, 1);
+    renderer.put_cell(0, 0, Cell::new('日', Color::Green));
+    renderer.put_cell(1, 0, Cell::new('x', Color::Green));
+    renderer.put_cell(2, 0, Cell::new('y', Color::Green));
+    renderer.flush().unwrap();
+
+    let output = String::from_utf8_lossy(renderer.writer()).into_owned();
+    assert!(output.contains("日y"), "expected y right after the wide glyph: {output:?}");
+    assert!(!output.contains('x'), "wrote into the right half of a wide glyph: {output:?}");
+
+    let mut memory = MemoryRenderer::new(4, 1);
+    memory.put_cell(0, 0, Cell::new('日', Color::Green));
+    memory.put_cell(2, 0, Cell::new('y', Color::Green));
+    memory.flush().unwrap();
+    assert_eq!(memory.lines(), vec!["日y ".to_string()]);
+}
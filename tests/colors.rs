@@ -0,0 +1,88 @@
+use crossterm::style::Color;
+use make_it_rain::colors::{ansi_256_to_rgb, parse_rgb};
+use make_it_rain::MatrixColorScheme;
+
+#[test]
+fn parses_hex_colors() {
+    assert_eq!(parse_rgb("#00ff41"), Ok((0, 255, 65)));
+    assert_eq!(parse_rgb("#00FF41"), Ok((0, 255, 65)));
+    assert_eq!(parse_rgb("#0f4"), Ok((0, 255, 68)));
+    assert!(parse_rgb("#00ff4").is_err());
+    assert!(parse_rgb("#gg0000").is_err());
+}
+
+#[test]
+fn parses_rgb_function() {
+    assert_eq!(parse_rgb("rgb(0,255,65)"), Ok((0, 255, 65)));
+    assert_eq!(parse_rgb("RGB( 12, 34 , 56 )"), Ok((12, 34, 56)));
+    assert!(parse_rgb("rgb(0,256,0)").is_err());
+    assert!(parse_rgb("rgb(1,2)").is_err());
+}
+
+#[test]
+fn parses_named_colors() {
+    assert_eq!(parse_rgb("lime"), Ok((0, 255, 0)));
+    assert_eq!(parse_rgb("aliceblue"), Ok((240, 248, 255)));
+    assert_eq!(parse_rgb("yellowgreen"), Ok((154, 205, 50)));
+    assert_eq!(parse_rgb("Dark Olive-Green"), Ok((85, 107, 47)));
+    assert_eq!(parse_rgb("navyblue"), Ok((0, 0, 128)));
+    assert!(parse_rgb("notacolor").is_err());
+}
+
+#[test]
+fn maps_256_color_palette() {
+    assert_eq!(ansi_256_to_rgb(16), (0, 0, 0));
+    assert_eq!(ansi_256_to_rgb(46), (0, 255, 0));
+    assert_eq!(ansi_256_to_rgb(231), (255, 255, 255));
+    assert_eq!(ansi_256_to_rgb(232), (8, 8, 8));
+    assert_eq!(ansi_256_to_rgb(255), (238, 238, 238));
+}
+
+#[test]
+fn schemes_carry_exact_rgb() {
+    let scheme = MatrixColorScheme::parse("#00ff41").unwrap();
+    assert_eq!(scheme.get_base_rgb(), (0, 255, 65));
+
+    let scheme = MatrixColorScheme::parse("rgb(10,20,30)").unwrap();
+    assert_eq!(scheme.get_base_rgb(), (10, 20, 30));
+
+    let scheme = MatrixColorScheme::parse("208").unwrap();
+    assert!(matches!(scheme, MatrixColorScheme::Custom(Color::AnsiValue(208))));
+    assert_eq!(scheme.get_base_rgb(), (255, 135, 0));
+}
+
+#[test]
+fn keeps_basic_ansi_codes() {
+    assert!(matches!(MatrixColorScheme::parse("10"), Ok(MatrixColorScheme::Green)));
+    assert!(matches!(MatrixColorScheme::parse("9"), Ok(MatrixColorScheme::Custom(Color::Red))));
+    assert!(MatrixColorScheme::parse("256").is_err());
+}
+
+#[test]
+fn quantizes_to_256_palette() {
+    use make_it_rain::colors::nearest_ansi_256;
+    assert_eq!(nearest_ansi_256((0, 255, 0)), 46);
+    assert_eq!(nearest_ansi_256((255, 255, 255)), 231);
+    assert_eq!(nearest_ansi_256((128, 128, 128)), 244);
+    assert_eq!(nearest_ansi_256((0, 250, 60)), 47);
+}
+
+#[test]
+fn fades_toward_the_background() {
+    use make_it_rain::colors::{fade_color_rgb, fade_toward};
+    let white = (255, 255, 255);
+    assert_eq!(fade_toward((0, 255, 0), white, 1.0), Color::Rgb { r: 0, g: 255, b: 0 });
+    assert_eq!(fade_toward((0, 255, 0), white, 0.0), Color::Rgb { r: 255, g: 255, b: 255 });
+    assert_eq!(fade_toward((0, 255, 0), white, 0.5), Color::Rgb { r: 127, g: 255, b: 127 });
+    assert_eq!(fade_color_rgb((0, 200, 0), 0.5), fade_toward((0, 200, 0), (0, 0, 0), 0.5));
+}
+
+#[test]
+fn parses_backgrounds() {
+    use make_it_rain::colors::Background;
+    assert_eq!(Background::parse("auto"), Ok(Background::Auto));
+    assert_eq!(Background::parse(" AUTO "), Ok(Background::Auto));
+    assert_eq!(Background::parse("#fdf6e3"), Ok(Background::Color(Color::Rgb { r: 253, g: 246, b: 227 })));
+    assert_eq!(Background::parse("15"), Ok(Background::Color(Color::White)));
+    assert!(Background::parse("nope").is_err());
+}
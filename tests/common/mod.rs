@@ -0,0 +1,166 @@
+//! Snapshot test harness: runs seeded simulations against an in-memory
+//! virtual terminal and compares the resulting grids to stored snapshots.
+//! Also holds fixtures shared by the other integration tests.
+//!
+//! Set `UPDATE_SNAPSHOTS=1` to (re)write the files in `tests/snapshots/`.
+
+#![allow(dead_code)]
+
+use make_it_rain::{
+    scheduler::SIMULATION_STEP, Cell, CellAttrs, MatrixConfig, MatrixSimulation, MemoryRenderer, Renderer,
+};
+use std::{fmt::Write as _, fs, path::PathBuf};
+
+/// A fresh scratch directory for one test
+pub fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("mir-test-{}-{name}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Read column `x` of a frame top to bottom, with empty cells as spaces
+pub fn column(simulation: &MatrixSimulation, x: u16) -> String {
+    let frame = simulation.frame();
+    (0..frame.rows()).map(|y| frame.get(x, y).unwrap().glyph).collect()
+}
+
+/// A seeded simulation wired to an in-memory terminal of a fixed size
+pub struct VirtualTerminal {
+    pub simulation: MatrixSimulation,
+    pub renderer: MemoryRenderer,
+    frames: u64,
+}
+
+impl VirtualTerminal {
+    pub fn new(config: MatrixConfig, cols: u16, rows: u16) -> Self {
+        Self {
+            simulation: MatrixSimulation::new(config, cols, rows),
+            renderer: MemoryRenderer::new(cols, rows),
+            frames: 0,
+        }
+    }
+
+    /// Tick one simulation step per frame and present each frame
+    pub fn step_frames(&mut self, frames: u32) {
+        for _ in 0..frames {
+            self.simulation.tick(SIMULATION_STEP);
+            self.present();
+        }
+    }
+
+    /// Deliver a terminal resize event
+    pub fn resize(&mut self, cols: u16, rows: u16) {
+        self.renderer.resize(cols, rows).unwrap();
+        self.simulation.resize(cols, rows);
+    }
+
+    fn present(&mut self) {
+        self.simulation.render(&mut self.renderer);
+        self.renderer.flush().unwrap();
+        self.frames += 1;
+    }
+
+    /// Every cell of the last presented frame, row by row
+    pub fn cells(&self) -> Vec<Vec<Cell>> {
+        let (cols, rows) = self.renderer.size();
+        (0..rows)
+            .map(|y| (0..cols).map(|x| *self.renderer.cell(x, y).unwrap()).collect())
+            .collect()
+    }
+
+    /// Text form of the last presented frame: a character grid, a color
+    /// grid with one letter per distinct color, and a legend for the letters
+    pub fn snapshot(&self) -> String {
+        let (cols, rows) = self.renderer.size();
+        let cells = self.cells();
+        let mut legend = Vec::new();
+        let mut out = String::new();
+
+        writeln!(out, "size: {cols}x{rows}").unwrap();
+        writeln!(out, "frames: {}", self.frames).unwrap();
+        writeln!(out, "--- chars").unwrap();
+        for row in &cells {
+            let line: String = row.iter().map(|cell| cell.ch).collect();
+            writeln!(out, "|{line}|").unwrap();
+        }
+
+        writeln!(out, "--- colors").unwrap();
+        for row in &cells {
+            let line: String = row
+                .iter()
+                .map(|cell| {
+                    if cell.ch == ' ' {
+                        return '.';
+                    }
+                    let index = legend.iter().position(|c| *c == cell.fg).unwrap_or_else(|| {
+                        legend.push(cell.fg);
+                        legend.len() - 1
+                    });
+                    color_key(index)
+                })
+                .collect();
+            writeln!(out, "|{line}|").unwrap();
+        }
+
+        writeln!(out, "--- legend").unwrap();
+        for (index, color) in legend.iter().enumerate() {
+            writeln!(out, "{} = {color:?}", color_key(index)).unwrap();
+        }
+
+        // Attributes are only listed when something uses them: B = bold, d = dim
+        let styled = cells.iter().flatten().any(|cell| cell.attrs != CellAttrs::default());
+        if styled {
+            writeln!(out, "--- attrs").unwrap();
+            for row in &cells {
+                let line: String = row
+                    .iter()
+                    .map(|cell| match (cell.attrs.bold, cell.attrs.dim) {
+                        (true, _) => 'B',
+                        (false, true) => 'd',
+                        _ => '.',
+                    })
+                    .collect();
+                writeln!(out, "|{line}|").unwrap();
+            }
+        }
+        out
+    }
+}
+
+fn color_key(index: usize) -> char {
+    const KEYS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+    KEYS.get(index).map_or('?', |&b| b as char)
+}
+
+/// Compare `actual` to `tests/snapshots/<name>.snap`
+pub fn assert_snapshot(name: &str, actual: &str) {
+    let path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "tests", "snapshots", &format!("{name}.snap")]
+        .iter()
+        .collect();
+
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::write(&path, actual).unwrap();
+        return;
+    }
+
+    let expected = fs::read_to_string(&path).unwrap_or_else(|_| {
+        panic!(
+            "missing snapshot {}; run with UPDATE_SNAPSHOTS=1 to create it",
+            path.display()
+        )
+    });
+
+    if expected != actual {
+        let diff: String = expected
+            .lines()
+            .zip(actual.lines())
+            .enumerate()
+            .filter(|(_, (e, a))| e != a)
+            .map(|(i, (e, a))| format!("line {}:\n  expected {e}\n  actual   {a}\n", i + 1))
+            .collect();
+        panic!(
+            "snapshot {name} does not match (run with UPDATE_SNAPSHOTS=1 to accept):\n{diff}"
+        );
+    }
+}
@@ -0,0 +1,20 @@
+use make_it_rain::MatrixConfig;
+
+#[test]
+fn trail_bounds_do_not_depend_on_call_order() {
+    let forward = MatrixConfig::new().with_min_trail(30).with_max_trail(35);
+    let backward = MatrixConfig::new().with_max_trail(35).with_min_trail(30);
+    for config in [forward, backward] {
+        assert_eq!((config.min_trail(), config.max_trail()), (30, 35));
+    }
+}
+
+#[test]
+fn trail_bounds_are_clamped_and_ordered() {
+    let config = MatrixConfig::new().with_min_trail(1).with_max_trail(100);
+    assert_eq!((config.min_trail(), config.max_trail()), (4, 40));
+
+    // A minimum above the maximum is capped at it
+    let config = MatrixConfig::new().with_min_trail(30).with_max_trail(20);
+    assert_eq!((config.min_trail(), config.max_trail()), (20, 20));
+}
@@ -0,0 +1,135 @@
+mod common;
+
+use std::io::Cursor;
+use std::time::{Duration, Instant};
+
+use common::column;
+use make_it_rain::{
+    charset, scheduler::SIMULATION_STEP, Charset, Feed, FeedMode, MatrixConfig, MatrixSimulation,
+};
+
+fn feed_config() -> MatrixConfig {
+    MatrixConfig::new()
+        .with_seed(Some(24))
+        .with_glitch_probability(0.0)
+        .with_flicker_probability(0.0)
+        .with_stuck(false)
+        .with_feed_only(true)
+}
+
+/// Everything a feed produces until its source ends
+fn drain_all(mut feed: Feed) -> Vec<String> {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let mut received = Vec::new();
+    while !feed.is_closed() {
+        assert!(Instant::now() < deadline, "feed never closed");
+        received.extend(feed.drain());
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    received
+}
+
+#[test]
+fn parses_modes() {
+    assert_eq!(FeedMode::parse("lines"), Ok(FeedMode::Lines));
+    assert_eq!(FeedMode::parse(" Stream "), Ok(FeedMode::Stream));
+    assert!(FeedMode::parse("words").is_err());
+}
+
+#[test]
+fn reads_lines() {
+    let feed = Feed::from_reader(Cursor::new("first line\nsecond\nno newline"), FeedMode::Lines);
+    assert_eq!(drain_all(feed), ["first line\n", "second\n", "no newline"]);
+}
+
+#[test]
+fn streams_without_waiting_for_line_breaks() {
+    let feed = Feed::from_reader(Cursor::new("partial output"), FeedMode::Stream);
+    assert_eq!(drain_all(feed).concat(), "partial output");
+}
+
+#[test]
+fn feed_only_starts_empty() {
+    let mut simulation = MatrixSimulation::new(feed_config(), 20, 10);
+    for _ in 0..120 {
+        simulation.tick(SIMULATION_STEP);
+    }
+    assert!(simulation.is_idle());
+    assert!(simulation.frame().iter().all(|(_, _, cell)| cell.glyph == ' '));
+}
+
+#[test]
+fn fed_text_falls_and_drains() {
+    let config = feed_config().with_min_trail(4).with_max_trail(12);
+    let mut simulation = MatrixSimulation::new(config, 1, 40);
+    simulation.feed("  ERROR\tdisk  \n");
+    assert_eq!(simulation.pending(), 1);
+
+    // The whole line reads top to bottom at some point on the way down
+    let mut seen = false;
+    for _ in 0..2000 {
+        simulation.tick(SIMULATION_STEP);
+        seen |= column(&simulation, 0).contains("ERROR disk");
+        if simulation.is_idle() {
+            break;
+        }
+    }
+    assert!(seen, "the fed line was never readable");
+    assert!(simulation.is_idle(), "the fed drop never finished falling");
+}
+
+#[test]
+fn long_text_is_split_over_several_drops() {
+    let config = feed_config().with_min_trail(4).with_max_trail(8);
+    let mut simulation = MatrixSimulation::new(config, 1, 10);
+    simulation.feed("abcdefghijklmnopqrst");
+    assert_eq!(simulation.pending(), 3);
+    simulation.feed(" \t ");
+    assert_eq!(simulation.pending(), 3);
+}
+
+#[test]
+fn pending_drops_are_capped() {
+    let mut simulation = MatrixSimulation::new(feed_config(), 1, 10);
+    for i in 0..10_000 {
+        simulation.feed(&i.to_string());
+    }
+    assert!(simulation.pending() <= 512);
+}
+
+#[test]
+fn stream_keeps_characters_split_across_reads() {
+    let text = "日".repeat(200) + "é";
+    let feed = Feed::from_reader(Cursor::new(text.clone()), FeedMode::Stream);
+    let received = drain_all(feed).concat();
+    assert!(!received.contains(char::REPLACEMENT_CHARACTER));
+    assert_eq!(received, text);
+}
+
+#[test]
+fn wide_fed_glyphs_fit_narrow_lanes() {
+    let mut simulation = MatrixSimulation::new(feed_config(), 4, 20);
+    assert_eq!(simulation.config().column_stride(), 1);
+    for _ in 0..4 {
+        simulation.feed("日本語のログ 🚀 ＯＫ");
+    }
+    for _ in 0..120 {
+        simulation.tick(SIMULATION_STEP);
+        for (_, _, cell) in simulation.frame().iter() {
+            assert_eq!(charset::char_width(cell.glyph), 1, "wide glyph {:?} in a narrow lane", cell.glyph);
+        }
+    }
+}
+
+#[test]
+fn wide_fed_glyphs_stay_with_a_wide_charset() {
+    let config = feed_config().with_charset(Charset::from_chars("日本").unwrap());
+    let mut simulation = MatrixSimulation::new(config, 4, 20);
+    simulation.feed("日本語");
+    let mut seen = false;
+    for _ in 0..120 {
+        simulation.tick(SIMULATION_STEP);
+        seen |= simulation.frame().iter().any(|(_, _, cell)| cell.glyph == '語');
+    }
+    assert!(seen);
+}
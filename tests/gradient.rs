@@ -0,0 +1,95 @@
+use make_it_rain::{Easing, Gradient, GradientDirection, SpatialGradient};
+
+#[test]
+fn spreads_unpositioned_stops_evenly() {
+    let gradient = Gradient::parse("white,#8f8,#0f0,#030").unwrap();
+    let positions: Vec<f32> = gradient.stops().iter().map(|s| s.position).collect();
+    assert_eq!(positions.len(), 4);
+    assert!((positions[1] - 1.0 / 3.0).abs() < 1e-6);
+    assert!((positions[2] - 2.0 / 3.0).abs() < 1e-6);
+    assert_eq!(positions[3], 1.0);
+}
+
+#[test]
+fn honours_explicit_positions() {
+    let gradient = Gradient::parse("white@0,lime@20%,#030").unwrap();
+    let positions: Vec<f32> = gradient.stops().iter().map(|s| s.position).collect();
+    assert_eq!(positions, vec![0.0, 0.2, 1.0]);
+
+    let gradient = Gradient::parse("white,red@0.8,black,blue").unwrap();
+    let positions: Vec<f32> = gradient.stops().iter().map(|s| s.position).collect();
+    assert_eq!(positions[0], 0.0);
+    assert!((positions[1] - 0.8).abs() < 1e-6);
+    assert!((positions[2] - 0.9).abs() < 1e-6);
+    assert_eq!(positions[3], 1.0);
+}
+
+#[test]
+fn accepts_rgb_function_stops() {
+    let gradient = Gradient::parse("rgb(255,255,255),rgb(0,0,0)").unwrap();
+    assert_eq!(gradient.stops().len(), 2);
+    assert_eq!(gradient.sample(0.5), (128, 128, 128));
+}
+
+#[test]
+fn samples_between_stops() {
+    let gradient = Gradient::parse("#000000,#ffffff").unwrap();
+    assert_eq!(gradient.sample(0.0), (0, 0, 0));
+    assert_eq!(gradient.sample(1.0), (255, 255, 255));
+    assert_eq!(gradient.sample(0.5), (128, 128, 128));
+    assert_eq!(gradient.sample(2.0), (255, 255, 255));
+}
+
+#[test]
+fn applies_easing_per_segment() {
+    let gradient = Gradient::parse("#000000,#ffffff").unwrap();
+    let ease_in = gradient.clone().with_easing(Easing::EaseIn);
+    let ease_out = gradient.clone().with_easing(Easing::EaseOut);
+    let step = gradient.with_easing(Easing::Step);
+
+    assert_eq!(ease_in.sample(0.5), (64, 64, 64));
+    assert_eq!(ease_out.sample(0.5), (191, 191, 191));
+    assert_eq!(step.sample(0.9), (0, 0, 0));
+}
+
+#[test]
+fn rejects_bad_specs() {
+    assert!(Gradient::parse("").is_err());
+    assert!(Gradient::parse("white,,black").is_err());
+    assert!(Gradient::parse("white@2,black").is_err());
+    assert!(Gradient::parse("white,notacolor").is_err());
+    assert!(Easing::parse("bouncy").is_err());
+    assert_eq!(Easing::parse("Ease_In_Out"), Ok(Easing::EaseInOut));
+}
+
+#[test]
+fn parses_directions() {
+    assert_eq!(GradientDirection::parse("horizontal"), Ok(GradientDirection::Horizontal));
+    assert_eq!(GradientDirection::parse("Vertical"), Ok(GradientDirection::Vertical));
+    assert_eq!(GradientDirection::parse("radial"), Ok(GradientDirection::Radial));
+    assert!(GradientDirection::parse("diagonal").is_err());
+}
+
+#[test]
+fn positions_cells_across_the_screen() {
+    let colors = Gradient::parse("#000000,#ffffff").unwrap();
+
+    let horizontal = SpatialGradient::new(GradientDirection::Horizontal, colors.clone());
+    assert_eq!(horizontal.position(0, 7, 11, 9), 0.0);
+    assert_eq!(horizontal.position(5, 0, 11, 9), 0.5);
+    assert_eq!(horizontal.position(10, 3, 11, 9), 1.0);
+    assert_eq!(horizontal.sample(10, 3, 11, 9), (255, 255, 255));
+
+    let vertical = SpatialGradient::new(GradientDirection::Vertical, colors.clone());
+    assert_eq!(vertical.position(10, 0, 11, 9), 0.0);
+    assert_eq!(vertical.position(0, 8, 11, 9), 1.0);
+
+    let radial = SpatialGradient::new(GradientDirection::Radial, colors);
+    assert_eq!(radial.position(5, 4, 11, 9), 0.0);
+    assert!((radial.position(0, 0, 11, 9) - 1.0).abs() < 1e-6);
+    assert!((radial.position(10, 8, 11, 9) - 1.0).abs() < 1e-6);
+    assert!((radial.position(0, 4, 11, 9) - 0.5f32.sqrt()).abs() < 1e-6);
+
+    // A single cell is its own center
+    assert_eq!(radial.position(0, 0, 1, 1), 0.0);
+}
@@ -0,0 +1,100 @@
+mod common;
+
+use std::fs;
+
+use common::scratch_dir;
+use make_it_rain::{charset::char_width, Charset, Palette, PaletteRegistry};
+
+#[test]
+fn looks_up_builtin_palettes() {
+    let registry = PaletteRegistry::new();
+    for name in ["classic", "katakana", "alphanumeric", "digits", "symbols", "greek"] {
+        assert!(!registry.lookup(name).unwrap().is_empty(), "{name}");
+    }
+    assert_eq!(registry.lookup(" Digits ").unwrap(), "0123456789".chars().collect::<Vec<_>>());
+}
+
+#[test]
+fn builtin_palettes_have_one_display_width_each() {
+    let registry = PaletteRegistry::new();
+    for palette in registry.iter() {
+        let width = palette.width();
+        assert!(
+            palette.chars.iter().all(|&c| char_width(c) == width),
+            "{} mixes display widths",
+            palette.name
+        );
+    }
+    for name in ["hangul", "kanji", "emoji"] {
+        assert_eq!(registry.find(name).unwrap().width(), 2, "{name}");
+    }
+    for name in ["classic", "binary", "hex", "braille", "box-drawing", "runic", "cyrillic", "devanagari", "math"] {
+        assert_eq!(registry.find(name).unwrap().width(), 1, "{name}");
+    }
+}
+
+#[test]
+fn charsets_measure_their_widest_character() {
+    assert_eq!(Charset::from_chars("01").unwrap().width(), 1);
+    assert_eq!(Charset::from_chars("0日").unwrap().width(), 2);
+    // Combining marks can't fill a cell on their own
+    assert_eq!(Charset::from_chars("e\u{301}").unwrap().chars().collect::<String>(), "e");
+}
+
+#[test]
+fn unknown_names_are_errors_with_suggestions() {
+    let registry = PaletteRegistry::new();
+    let err = registry.lookup("greeek").unwrap_err();
+    assert!(err.contains("did you mean 'greek'"), "{err}");
+
+    let err = registry.lookup("plaid").unwrap_err();
+    assert!(err.contains("katakana") && err.contains("symbols"), "{err}");
+
+    assert!(registry.parse("katakana+greeek").is_err());
+    assert_eq!(registry.parse("katakana:3,digits").unwrap().groups().len(), 2);
+}
+
+#[test]
+fn parses_user_palette_files() {
+    let palette = Palette::from_file_contents("Runes", "# Elder futhark\nᚠᚢᚦ ᚨ\n#ᚱ\nᚲ\n").unwrap();
+    assert_eq!(palette.name, "runes");
+    assert_eq!(palette.description, "Elder futhark");
+    assert_eq!(palette.chars.iter().collect::<String>(), "ᚠᚢᚦᚨᚲ");
+    assert!(palette.user);
+
+    assert!(Palette::from_file_contents("empty", "# nothing here\n \n").is_err());
+    assert!(Palette::from_file_contents("a+b", "xyz").is_err());
+    assert!(Palette::from_file_contents("a:b", "xyz").is_err());
+}
+
+#[test]
+fn loads_user_palettes_from_a_directory() {
+    let dir = scratch_dir("load");
+    fs::write(dir.join("binary.txt"), "01").unwrap();
+    fs::write(dir.join("digits.txt"), "# Only odd digits\n13579").unwrap();
+    fs::write(dir.join("blank.txt"), "\n").unwrap();
+    fs::write(dir.join("notes.md"), "not a palette").unwrap();
+
+    let mut registry = PaletteRegistry::new();
+    let errors = registry.load_dir(&dir);
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].contains("blank.txt"), "{}", errors[0]);
+    assert_eq!(registry.lookup("binary").unwrap(), vec!['0', '1']);
+    assert!(registry.find("notes").is_none());
+
+    // User palettes replace built-ins of the same name
+    let digits = registry.find("digits").unwrap();
+    assert!(digits.user);
+    assert_eq!(digits.chars.iter().collect::<String>(), "13579");
+    assert_eq!(registry.names().iter().filter(|&&name| name == "digits").count(), 1);
+}
+
+#[test]
+fn missing_directory_loads_nothing() {
+    let mut registry = PaletteRegistry::new();
+    let before = registry.names().len();
+    assert!(registry.load_dir(&std::env::temp_dir().join("mir-no-such-dir")).is_empty());
+    assert_eq!(registry.names().len(), before);
+}
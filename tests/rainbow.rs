@@ -0,0 +1,99 @@
+mod common;
+
+use common::VirtualTerminal;
+use crossterm::style::Color;
+use make_it_rain::{colors::hsv_to_rgb, MatrixConfig, MatrixSimulation, Rainbow, RainbowMode};
+
+fn run(rainbow: Rainbow, frames: u32) -> MatrixSimulation {
+    let config = MatrixConfig::new()
+        .with_seed(Some(8))
+        .with_initial_drops(16)
+        .with_rgb_fade(true)
+        .with_glitch_probability(0.0)
+        .with_stuck(false)
+        .with_rainbow(Some(rainbow));
+    let mut term = VirtualTerminal::new(config, 24, 12);
+    term.step_frames(frames);
+    term.simulation
+}
+
+/// Hue of every visible non-head cell, grouped by column
+fn trail_colors(simulation: &MatrixSimulation) -> Vec<(u16, (u8, u8, u8))> {
+    simulation
+        .frame()
+        .iter()
+        .filter_map(|(x, _, cell)| match cell.color {
+            Color::Rgb { r, g, b } if (r, g, b) != (255, 255, 255) => Some((x, (r, g, b))),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn converts_hsv() {
+    assert_eq!(hsv_to_rgb(0.0, 1.0, 1.0), (255, 0, 0));
+    assert_eq!(hsv_to_rgb(1.0 / 3.0, 1.0, 1.0), (0, 255, 0));
+    assert_eq!(hsv_to_rgb(2.0 / 3.0, 1.0, 1.0), (0, 0, 255));
+    assert_eq!(hsv_to_rgb(1.5, 1.0, 1.0), (0, 255, 255));
+    assert_eq!(hsv_to_rgb(0.3, 0.0, 1.0), (255, 255, 255));
+    assert_eq!(hsv_to_rgb(0.0, 0.5, 1.0), (255, 128, 128));
+}
+
+#[test]
+fn parses_modes() {
+    assert_eq!(RainbowMode::parse("time"), Ok(RainbowMode::Time));
+    assert_eq!(RainbowMode::parse("Column"), Ok(RainbowMode::Column));
+    assert_eq!(RainbowMode::parse("drops"), Ok(RainbowMode::Drop));
+    assert!(RainbowMode::parse("diagonal").is_err());
+}
+
+#[test]
+fn hue_follows_mode() {
+    let time = Rainbow::new(RainbowMode::Time).with_speed(0.5);
+    assert_eq!(time.hue(3, 10, 0.7, 0.0), 0.0);
+    assert_eq!(time.hue(3, 10, 0.7, 1.0), 0.5);
+    assert_eq!(time.hue(3, 10, 0.7, 2.0), 0.0);
+
+    let column = Rainbow::new(RainbowMode::Column).with_speed(0.0);
+    assert_eq!(column.hue(0, 10, 0.7, 5.0), 0.0);
+    assert_eq!(column.hue(5, 10, 0.7, 5.0), 0.5);
+
+    let drop = Rainbow::new(RainbowMode::Drop).with_speed(-0.25);
+    assert_eq!(drop.hue(3, 10, 0.5, 1.0), 0.25);
+}
+
+#[test]
+fn saturation_is_applied_and_clamped() {
+    let pastel = Rainbow::new(RainbowMode::Time).with_saturation(0.5);
+    assert_eq!(pastel.rgb(0, 1, 0.0, 0.0), (255, 128, 128));
+    assert_eq!(Rainbow::new(RainbowMode::Time).with_saturation(3.0).saturation(), 1.0);
+}
+
+#[test]
+fn time_mode_shifts_every_drop_together() {
+    let rainbow = Rainbow::new(RainbowMode::Time).with_speed(0.25);
+    let half_turn = run(rainbow, 120);
+    let full_turn = run(rainbow, 240);
+
+    // At 0.25 cycles per second, two seconds is half a turn (cyan) and four a full one (red)
+    let dominant = |simulation: &MatrixSimulation| {
+        let colors = trail_colors(simulation);
+        let red = colors.iter().filter(|(_, (r, g, b))| r > g && r > b).count();
+        (red, colors.len() - red)
+    };
+    let (half_red, half_other) = dominant(&half_turn);
+    let (full_red, full_other) = dominant(&full_turn);
+    assert_eq!(half_red, 0, "time mode left red trails after half a cycle");
+    assert!(half_other > 0);
+    assert!(full_red > 0 && full_other == 0, "trails did not return to red after a full cycle");
+}
+
+#[test]
+fn column_mode_varies_across_the_screen() {
+    let simulation = run(Rainbow::new(RainbowMode::Column).with_speed(0.0), 120);
+    let colors = trail_colors(&simulation);
+    let left = colors.iter().find(|(x, _)| *x < 4).map(|(_, rgb)| *rgb).unwrap();
+    let right = colors.iter().find(|(x, _)| *x >= 12 && *x < 20).map(|(_, rgb)| *rgb).unwrap();
+    assert!(left.0 > left.2, "left columns should be red-ish: {left:?}");
+    assert!(right.2 > right.0, "right columns should be blue-ish: {right:?}");
+}
@@ -0,0 +1,70 @@
+use crossterm::style::Color;
+use make_it_rain::{Cell, CrosstermRenderer, Renderer};
+
+const GREEN: Color = Color::Rgb { r: 0, g: 255, b: 70 };
+
+/// Draw a fresh frame of `cells` and return what was written to the terminal
+fn draw(renderer: &mut CrosstermRenderer<Vec<u8>>, cells: &[(u16, u16, char)]) -> String {
+    renderer.writer().clear();
+    renderer.clear();
+    for &(x, y, ch) in cells {
+        renderer.put_cell(x, y, Cell::new(ch, GREEN));
+    }
+    renderer.flush().unwrap();
+    String::from_utf8(renderer.writer().clone()).unwrap()
+}
+
+/// Split terminal output into its escape sequences and the text printed
+fn parse(output: &str) -> (Vec<String>, String) {
+    let mut escapes = Vec::new();
+    let mut text = String::new();
+    let mut chars = output.chars();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            text.push(c);
+            continue;
+        }
+        // CSI sequences run up to a final byte in @..~
+        let mut escape = String::from(c);
+        for c in chars.by_ref() {
+            escape.push(c);
+            if escape.len() > 2 && ('@'..='~').contains(&c) {
+                break;
+            }
+        }
+        escapes.push(escape);
+    }
+    (escapes, text)
+}
+
+#[test]
+fn unchanged_frames_write_nothing() {
+    let mut renderer = CrosstermRenderer::new(Vec::new(), 8, 4);
+    let frame = [(0, 0, 'a'), (3, 1, 'b'), (7, 3, 'c')];
+    let first = draw(&mut renderer, &frame);
+    assert_eq!(parse(&first).1, "abc");
+
+    assert_eq!(draw(&mut renderer, &frame), "");
+}
+
+#[test]
+fn one_changed_cell_is_one_move_and_one_print() {
+    let mut renderer = CrosstermRenderer::new(Vec::new(), 8, 4);
+    draw(&mut renderer, &[(0, 0, 'a'), (3, 1, 'b'), (7, 3, 'c')]);
+
+    let output = draw(&mut renderer, &[(0, 0, 'a'), (3, 1, 'z'), (7, 3, 'c')]);
+    let (escapes, text) = parse(&output);
+    assert_eq!(text, "z");
+    let moves: Vec<&String> = escapes.iter().filter(|escape| escape.ends_with('H')).collect();
+    assert_eq!(moves, ["\x1b[2;4H"], "unexpected output {output:?}");
+}
+
+#[test]
+fn cleared_cells_are_blanked() {
+    let mut renderer = CrosstermRenderer::new(Vec::new(), 8, 4);
+    draw(&mut renderer, &[(0, 0, 'a'), (3, 1, 'b')]);
+
+    let (escapes, text) = parse(&draw(&mut renderer, &[(0, 0, 'a')]));
+    assert_eq!(text, " ");
+    assert_eq!(escapes.iter().filter(|escape| escape.ends_with('H')).count(), 1);
+}
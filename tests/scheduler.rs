@@ -0,0 +1,30 @@
+use std::thread::sleep;
+use std::time::Duration;
+
+use make_it_rain::scheduler::{FrameScheduler, SIMULATION_STEP};
+
+#[test]
+fn frames_shorter_than_a_step_still_advance() {
+    // At 144 fps a frame lasts less than one simulation step
+    let mut scheduler = FrameScheduler::new(144);
+    scheduler.begin_frame();
+    sleep(Duration::from_millis(5));
+    let elapsed = scheduler.begin_frame();
+    assert!(elapsed >= Duration::from_millis(5));
+    assert!(elapsed < SIMULATION_STEP * 10);
+}
+
+#[test]
+fn long_stalls_are_not_replayed() {
+    let mut scheduler = FrameScheduler::new(60);
+    sleep(Duration::from_millis(400));
+    assert!(scheduler.begin_frame() <= Duration::from_millis(250));
+}
+
+#[test]
+fn reset_discards_elapsed_time() {
+    let mut scheduler = FrameScheduler::new(60);
+    sleep(Duration::from_millis(20));
+    scheduler.reset();
+    assert!(scheduler.begin_frame() < Duration::from_millis(20));
+}
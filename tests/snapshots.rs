@@ -0,0 +1,290 @@
+mod common;
+
+use std::time::Duration;
+
+use common::{assert_snapshot, VirtualTerminal};
+use crossterm::style::Color;
+use make_it_rain::{
+    charset::{char_width, is_reliably_narrow},
+    colors::Background,
+    scheduler::SIMULATION_STEP,
+    themes,
+    Easing, FrameCell, Gradient, GradientDirection, MatrixConfig, PaletteRegistry, SpatialGradient,
+};
+
+/// Stuck characters get a color no trail uses, so tests can pick them out
+const STUCK_COLOR: Color = Color::Magenta;
+
+fn seeded(seed: u64) -> MatrixConfig {
+    MatrixConfig::new().with_seed(Some(seed)).with_initial_drops(12)
+}
+
+#[test]
+fn default_rain() {
+    let mut term = VirtualTerminal::new(seeded(1), 32, 16);
+    term.step_frames(150);
+    assert_snapshot("default_rain", &term.snapshot());
+}
+
+#[test]
+fn rgb_fade_rain() {
+    let mut term = VirtualTerminal::new(seeded(2).with_rgb_fade(true), 24, 12);
+    term.step_frames(120);
+    assert_snapshot("rgb_fade_rain", &term.snapshot());
+}
+
+#[test]
+fn light_background_rain() {
+    let background = Background::Color(Color::Rgb { r: 253, g: 246, b: 227 });
+    let plain = seeded(2).with_rgb_fade(true);
+    // Themes color trails with a gradient, which must blend into the background too
+    let themed = themes::parse_theme("matrix").unwrap().apply(seeded(2).with_stuck(false)).with_rgb_fade(true);
+
+    for (name, config) in [("light_background_rain", plain), ("light_background_theme_rain", themed)] {
+        let mut term = VirtualTerminal::new(config.with_background(Some(background)), 16, 8);
+        term.step_frames(120);
+        assert_snapshot(name, &term.snapshot());
+
+        // Trails fade toward the background, so nothing darker than the base green remains
+        for cell in term.cells().iter().flatten().filter(|cell| cell.ch != ' ') {
+            if let Color::Rgb { r, b, .. } = cell.fg {
+                assert!(r > 0 && b > 0, "{name}: trail faded toward black: {:?}", cell.fg);
+            }
+        }
+    }
+}
+
+#[test]
+fn gradient_rain() {
+    let gradient = Gradient::parse("white,#8f8,#0f0@40%,#030").unwrap().with_easing(Easing::EaseOut);
+    let config = seeded(9).with_gradient(Some(gradient));
+    let mut indexed = VirtualTerminal::new(config.clone(), 24, 12);
+    indexed.step_frames(120);
+    assert_snapshot("gradient_rain_indexed", &indexed.snapshot());
+
+    let mut rgb = VirtualTerminal::new(config.with_rgb_fade(true), 24, 12);
+    rgb.step_frames(120);
+    assert_snapshot("gradient_rain_rgb", &rgb.snapshot());
+}
+
+#[test]
+fn spatial_rain() {
+    let colors = Gradient::parse("lime,cyan").unwrap();
+    let spatial = SpatialGradient::new(GradientDirection::Horizontal, colors);
+    let config = seeded(10).with_spatial_gradient(Some(spatial));
+    let mut term = VirtualTerminal::new(config, 24, 12);
+    term.step_frames(120);
+    assert_snapshot("spatial_rain", &term.snapshot());
+}
+
+#[test]
+fn styled_heads() {
+    let config = seeded(11)
+        .with_head_color(Some(Color::Rgb { r: 230, g: 255, b: 230 }))
+        .with_bold_head(Some(2))
+        .with_dim_tail(true)
+        .with_glow(true);
+    let mut term = VirtualTerminal::new(config, 20, 10);
+    term.step_frames(100);
+    assert_snapshot("styled_heads", &term.snapshot());
+}
+
+#[test]
+fn glow_lights_the_cell_ahead_of_the_head() {
+    let config = seeded(12)
+        .with_glitch_probability(0.0)
+        .with_flicker_probability(0.0)
+        .with_stuck(false);
+    let mut plain = VirtualTerminal::new(config.clone(), 20, 10);
+    let mut glowing = VirtualTerminal::new(config.with_glow(true), 20, 10);
+
+    let mut lit = 0;
+    for _ in 0..60 {
+        plain.step_frames(1);
+        glowing.step_frames(1);
+        for (plain_row, glow_row) in plain.cells().iter().zip(glowing.cells()) {
+            for (x, (before, after)) in plain_row.iter().zip(&glow_row).enumerate() {
+                if before != after {
+                    // Glow only ever adds a cell directly below a drop's head
+                    assert_eq!(before.ch, ' ', "glow overwrote a trail cell in column {x}");
+                    lit += 1;
+                }
+            }
+        }
+    }
+    assert!(lit > 0, "glow never lit a cell");
+}
+
+#[test]
+fn mono_rain() {
+    let mut term = VirtualTerminal::new(seeded(13).with_mono(true).with_rgb_fade(true), 20, 10);
+    term.step_frames(120);
+    assert_snapshot("mono_rain", &term.snapshot());
+
+    let cells = term.cells();
+    let drawn: Vec<_> = cells.iter().flatten().filter(|cell| cell.ch != ' ').collect();
+    assert!(!drawn.is_empty());
+    assert!(drawn.iter().all(|cell| cell.fg == Color::Reset), "mono mode emitted a color");
+    assert!(drawn.iter().any(|cell| cell.attrs.bold) && drawn.iter().any(|cell| cell.attrs.dim));
+}
+
+#[test]
+fn monochrome_cells_follow_intensity() {
+    let cell = |intensity| FrameCell::new('ﾊ', Color::Green, intensity).monochrome();
+    assert_eq!((cell(1.0).glyph, cell(1.0).attrs.bold, cell(1.0).attrs.dim), ('ﾊ', true, false));
+    assert_eq!((cell(0.5).glyph, cell(0.5).attrs.bold, cell(0.5).attrs.dim), ('ﾊ', false, false));
+    assert_eq!((cell(0.3).glyph, cell(0.3).attrs.bold, cell(0.3).attrs.dim), ('ﾊ', false, true));
+    assert_eq!(cell(0.15).glyph, ':');
+    assert_eq!(cell(0.05).glyph, '.');
+    assert_eq!(cell(0.5).color, Color::Reset);
+    assert!(FrameCell::EMPTY.monochrome().is_empty());
+}
+
+#[test]
+fn resize_mid_run() {
+    let mut term = VirtualTerminal::new(seeded(3), 32, 16);
+    term.step_frames(90);
+    term.resize(20, 10);
+    term.step_frames(120);
+    assert_snapshot("resize_mid_run", &term.snapshot());
+}
+
+#[test]
+fn sticky_characters() {
+    let config = seeded(4)
+        .with_min_trail(4)
+        .with_max_trail(4)
+        .with_stuck_probability(1.0)
+        .with_sticky_color(Some(STUCK_COLOR))
+        .with_new_drop_probability(0.0)
+        .with_glitch_probability(0.0)
+        .with_flicker_probability(0.0)
+        .with_speed(4.0);
+    let mut term = VirtualTerminal::new(config, 16, 8);
+
+    // Long enough for every initial drop to fall off screen and stick
+    term.step_frames(60);
+
+    let stuck = term
+        .simulation
+        .frame()
+        .iter()
+        .filter(|(_, _, cell)| cell.color == STUCK_COLOR)
+        .count();
+    assert!(stuck > 0, "expected stuck characters to remain on screen");
+    assert_snapshot("sticky_characters", &term.snapshot());
+}
+
+#[test]
+fn no_stuck_leaves_no_sticky_characters() {
+    let config = seeded(4)
+        .with_min_trail(4)
+        .with_max_trail(4)
+        .with_stuck(false)
+        .with_stuck_probability(1.0)
+        .with_sticky_color(Some(STUCK_COLOR))
+        .with_new_drop_probability(0.0)
+        .with_speed(4.0);
+    let mut term = VirtualTerminal::new(config, 16, 8);
+    term.step_frames(60);
+
+    assert!(term.simulation.frame().iter().all(|(_, _, cell)| cell.color != STUCK_COLOR));
+}
+
+#[test]
+fn same_seed_replays_same_frames() {
+    let mut a = VirtualTerminal::new(seeded(5), 24, 12);
+    let mut b = VirtualTerminal::new(seeded(5), 24, 12);
+    for _ in 0..40 {
+        a.step_frames(3);
+        b.step_frames(3);
+        assert_eq!(a.cells(), b.cells());
+    }
+}
+
+#[test]
+fn frames_do_not_depend_on_tick_size() {
+    let mut fine = VirtualTerminal::new(seeded(6), 24, 12);
+    fine.step_frames(120);
+
+    let mut coarse = VirtualTerminal::new(seeded(6), 24, 12);
+    coarse.simulation.tick(SIMULATION_STEP * 120);
+
+    assert_eq!(fine.simulation.frame().cells(), coarse.simulation.frame().cells());
+
+    // Ticks that aren't whole steps cover the same simulated time too
+    let ticked = |count: u32, millis: u64| {
+        let mut term = VirtualTerminal::new(seeded(6), 24, 12);
+        for _ in 0..count {
+            term.simulation.tick(Duration::from_millis(millis));
+        }
+        term.simulation.frame().cells().to_vec()
+    };
+    assert_eq!(ticked(100, 20), ticked(80, 25));
+    assert_eq!(ticked(33, 7), ticked(7, 33));
+}
+
+#[test]
+fn high_frame_rates_draw_new_frames() {
+    // Ticks shorter than a simulation step still move the rain on, so a
+    // second at 144 fps shows more distinct frames than one at 60 fps
+    let distinct_frames = |fps: u32| {
+        let config = seeded(6).with_glow(true).with_flicker_probability(0.0).with_glitch_probability(0.0);
+        let mut term = VirtualTerminal::new(config, 24, 12);
+        let mut previous = term.simulation.frame().cells().to_vec();
+        let mut changed = 0;
+        for _ in 0..fps {
+            term.simulation.tick(Duration::from_secs(1) / fps);
+            let cells = term.simulation.frame().cells();
+            changed += usize::from(cells != previous);
+            previous = cells.to_vec();
+        }
+        changed
+    };
+    let (slow, fast) = (distinct_frames(60), distinct_frames(144));
+    assert!(fast > slow, "{fast} distinct frames at 144 fps, {slow} at 60 fps");
+}
+
+#[test]
+fn different_seeds_differ() {
+    let mut a = VirtualTerminal::new(seeded(7), 24, 12);
+    let mut b = VirtualTerminal::new(seeded(8), 24, 12);
+    a.step_frames(120);
+    b.step_frames(120);
+    assert_ne!(a.cells(), b.cells());
+}
+
+#[test]
+fn wide_palette_rain() {
+    let palettes = PaletteRegistry::new();
+    let config = seeded(14).with_charset(palettes.parse("kanji").unwrap());
+    assert_eq!(config.column_stride(), 2);
+    let mut term = VirtualTerminal::new(config, 24, 10);
+    term.step_frames(120);
+    assert_snapshot("wide_palette_rain", &term.snapshot());
+
+    // Drops keep to even columns, leaving room for the right half of each glyph
+    for (x, _, cell) in term.simulation.frame().iter() {
+        assert_eq!(x % 2, 0, "{:?} drawn at odd column {x}", cell.glyph);
+    }
+    for line in term.renderer.lines() {
+        let cells: usize = line.chars().map(char_width).sum();
+        assert_eq!(cells, 24, "line overflows the terminal: {line:?}");
+    }
+}
+
+#[test]
+fn narrow_mode_replaces_wide_and_ambiguous_glyphs() {
+    let palettes = PaletteRegistry::new();
+    let config = seeded(15)
+        .with_charset(palettes.parse("kanji+greek+box-drawing").unwrap())
+        .with_glitch_probability(0.1)
+        .with_narrow(true);
+    assert_eq!(config.column_stride(), 1);
+    let mut term = VirtualTerminal::new(config, 20, 10);
+    term.step_frames(120);
+
+    let drawn: Vec<char> = term.simulation.frame().iter().map(|(_, _, cell)| cell.glyph).collect();
+    assert!(!drawn.is_empty());
+    assert!(drawn.iter().all(|&c| is_reliably_narrow(c)), "wide glyph in {drawn:?}");
+}
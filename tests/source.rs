@@ -0,0 +1,125 @@
+mod common;
+
+use std::fs;
+
+use common::scratch_dir;
+use make_it_rain::{
+    scheduler::SIMULATION_STEP, source::glob_match, MatrixConfig, MatrixRng, MatrixSimulation,
+    SourceLoader, SourceText, Whitespace,
+};
+use rand::SeedableRng;
+
+/// The whole prepared text of a single-line source, read once through
+fn sample_all(text: &str, whitespace: Whitespace, len: usize) -> String {
+    let source = SourceText::from_texts([text], whitespace).unwrap();
+    source.sample(len, &mut MatrixRng::seed_from_u64(0)).into_iter().collect()
+}
+
+#[test]
+fn globs_match_names_and_paths() {
+    assert!(glob_match("*.rs", "main.rs"));
+    assert!(!glob_match("*.rs", "main.rs.bak"));
+    assert!(glob_match("mod.?s", "mod.rs"));
+    assert!(glob_match("src/*.rs", "src/lib.rs"));
+    assert!(!glob_match("src/*.rs", "src/bin/mir.rs"));
+    assert!(glob_match("src/**/*.rs", "src/bin/mir.rs"));
+    assert!(glob_match("src/**/*.rs", "src/lib.rs"));
+    assert!(glob_match("**", "any/thing"));
+}
+
+#[test]
+fn whitespace_modes() {
+    let text = "    let  x =\t1;";
+    assert_eq!(sample_all(text, Whitespace::Collapse, 11), "let x = 1; ");
+    assert_eq!(sample_all(text, Whitespace::Strip, 6), "letx=1");
+    assert_eq!(sample_all(text, Whitespace::Keep, 16), "    let  x = 1; ");
+    assert_eq!(Whitespace::parse("STRIP"), Ok(Whitespace::Strip));
+    assert!(Whitespace::parse("squash").is_err());
+}
+
+#[test]
+fn samples_start_at_a_line_and_run_on() {
+    let source = SourceText::from_texts(["alpha\n\n   \nbeta\n"], Whitespace::Collapse).unwrap();
+    let mut rng = MatrixRng::seed_from_u64(3);
+    for _ in 0..20 {
+        let run: String = source.sample(8, &mut rng).into_iter().collect();
+        assert!(["alpha be", "beta alp"].contains(&run.as_str()), "{run:?}");
+    }
+    assert!(SourceText::from_texts(["", " \n\t"], Whitespace::Collapse).is_err());
+}
+
+#[test]
+fn loader_skips_binaries_hidden_files_build_dirs_and_unmatched_globs() {
+    let dir = scratch_dir("load");
+    fs::create_dir_all(dir.join("src/nested")).unwrap();
+    fs::create_dir_all(dir.join(".git")).unwrap();
+    fs::create_dir_all(dir.join("target/debug")).unwrap();
+    fs::create_dir_all(dir.join("web/node_modules/left-pad")).unwrap();
+    fs::write(dir.join("src/lib.rs"), "fn lib() {}").unwrap();
+    fs::write(dir.join("target/debug/build.rs"), "fn generated() {}").unwrap();
+    fs::write(dir.join("web/node_modules/left-pad/index.js"), "module.exports = pad;").unwrap();
+    fs::write(dir.join("src/nested/deep.rs"), "fn deep() {}").unwrap();
+    fs::write(dir.join("README.md"), "# readme").unwrap();
+    fs::write(dir.join(".git/HEAD"), "ref: main").unwrap();
+    fs::write(dir.join("logo.png"), [0x89, b'P', b'N', b'G', 0, 0, 1]).unwrap();
+    fs::write(dir.join("latin1.txt"), [b'c', b'a', b'f', 0xE9]).unwrap();
+
+    let all = SourceLoader::new().load(std::slice::from_ref(&dir)).unwrap();
+    let rust = SourceLoader::new().with_globs(vec!["*.rs".into()]).load(std::slice::from_ref(&dir)).unwrap();
+    let top = SourceLoader::new().with_globs(vec!["src/*.rs".into()]).load(std::slice::from_ref(&dir)).unwrap();
+    let none = SourceLoader::new().with_globs(vec!["*.py".into()]).load(std::slice::from_ref(&dir));
+    let file = SourceLoader::new().with_globs(vec!["*.py".into()]).load(&[dir.join("README.md")]).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(all.files(), 3);
+    assert_eq!(rust.files(), 2);
+    assert_eq!(top.files(), 1);
+    assert!(none.unwrap_err().contains("no readable text files"));
+    assert_eq!(file.files(), 1, "files named on the command line ignore globs");
+}
+
+#[test]
+fn loader_stops_at_the_total_size_limit() {
+    let dir = scratch_dir("limit");
+    for name in ["a.txt", "b.txt", "c.txt"] {
+        fs::write(dir.join(name), "x".repeat(100)).unwrap();
+    }
+    let capped = SourceLoader::new().with_max_total_size(250).load(std::slice::from_ref(&dir)).unwrap();
+    let tiny = SourceLoader::new().with_max_total_size(50).load(std::slice::from_ref(&dir));
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(capped.files(), 2);
+    assert!(tiny.is_err());
+}
+
+#[test]
+fn drops_carry_source_text_top_to_bottom() {
+    let text = "THE MATRIX HAS YOU";
+    let source = SourceText::from_texts([text], Whitespace::Strip).unwrap();
+    let config = MatrixConfig::new()
+        .with_seed(Some(23))
+        .with_initial_drops(6)
+        .with_min_trail(6)
+        .with_max_trail(6)
+        .with_glitch_probability(0.0)
+        .with_flicker_probability(0.0)
+        .with_stuck(false)
+        .with_source(Some(source));
+    let mut simulation = MatrixSimulation::new(config, 6, 40);
+    for _ in 0..90 {
+        simulation.tick(SIMULATION_STEP);
+    }
+
+    // Every full trail on screen is a run of the text, read downwards
+    let cycled = "THEMATRIXHASYOU".repeat(2);
+    let frame = simulation.frame();
+    let mut trails = 0;
+    for x in 0..6 {
+        let column: String = (0..40).map(|y| frame.get(x, y).unwrap().glyph).collect();
+        for run in column.split(' ').filter(|run| run.chars().count() == 6) {
+            assert!(cycled.contains(run), "{run:?} is not from the source");
+            trails += 1;
+        }
+    }
+    assert!(trails > 0, "no complete trail on screen");
+}
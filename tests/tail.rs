@@ -0,0 +1,143 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use make_it_rain::{
+    colors::color_to_rgb, scheduler::SIMULATION_STEP, Feed, FeedMode, HighlightRule, Highlighter,
+    MatrixConfig, MatrixSimulation, TailReader,
+};
+
+/// A fresh path in the temp directory, unique to this test
+fn temp_log(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("mir-tail-{}-{name}.log", std::process::id()));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+fn append(path: &Path, text: &str) {
+    let mut file = OpenOptions::new().create(true).append(true).open(path).unwrap();
+    file.write_all(text.as_bytes()).unwrap();
+}
+
+/// Follow `path` with a short poll interval so tests run quickly
+fn follow(path: &Path) -> Feed {
+    let reader = TailReader::open(path).unwrap().with_poll_interval(Duration::from_millis(5));
+    Feed::from_reader(reader, FeedMode::Lines)
+}
+
+/// Wait until the feed has produced `count` lines
+fn receive(feed: &mut Feed, count: usize) -> Vec<String> {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let mut lines = Vec::new();
+    while lines.len() < count {
+        assert!(Instant::now() < deadline, "only received {lines:?}");
+        lines.extend(feed.drain());
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    lines
+}
+
+#[test]
+fn parses_rules() {
+    let rule = HighlightRule::parse("status=5\\d\\d=#ff0000").unwrap();
+    assert_eq!(rule.pattern(), "status=5\\d\\d");
+    assert_eq!(rule.color(), (255, 0, 0));
+    assert!(rule.is_match("GET / status=503"));
+
+    assert!(HighlightRule::parse("ERROR").is_err());
+    assert!(HighlightRule::parse("=red").is_err());
+    assert!(HighlightRule::parse("(=red").is_err());
+    assert!(HighlightRule::parse("ERROR=notacolor").is_err());
+}
+
+#[test]
+fn first_matching_rule_wins() {
+    let highlighter = Highlighter::new(vec![
+        HighlightRule::parse("timeout=orange").unwrap(),
+        HighlightRule::parse("db=blue").unwrap(),
+    ]);
+    assert_eq!(highlighter.color("db timeout"), Some((255, 165, 0)));
+    assert_eq!(highlighter.color("db ok"), Some((0, 0, 255)));
+    assert_eq!(highlighter.color("all good"), None);
+}
+
+#[test]
+fn log_levels_color_errors_and_warnings() {
+    let highlighter = Highlighter::log_levels();
+    assert!(highlighter.color("[ERROR] disk full").is_some());
+    assert!(highlighter.color("level=warn msg=slow").is_some());
+    assert_ne!(highlighter.color("FATAL"), highlighter.color("Warning"));
+    assert_eq!(highlighter.color("INFO terrorism report"), None);
+}
+
+#[test]
+fn fed_lines_take_their_rule_color() {
+    let config = MatrixConfig::new()
+        .with_seed(Some(25))
+        .with_glitch_probability(0.0)
+        .with_flicker_probability(0.0)
+        .with_stuck(false)
+        .with_rgb_fade(true)
+        .with_feed_only(true)
+        .with_highlighter(Some(Highlighter::new(vec![HighlightRule::parse("ERROR=red").unwrap()])));
+    let mut simulation = MatrixSimulation::new(config, 2, 30);
+    simulation.feed("ERROR disk");
+    simulation.feed("INFO booted");
+
+    // Trails of the error line are shades of red; the other line keeps the rain's green
+    let (mut red, mut green) = (0, 0);
+    for _ in 0..60 {
+        simulation.tick(SIMULATION_STEP);
+        for (_, _, cell) in simulation.frame().iter().filter(|(_, _, cell)| cell.intensity < 1.0) {
+            if let Some((r, g, b)) = color_to_rgb(cell.color) {
+                red += usize::from(r > 0 && g == 0 && b == 0);
+                green += usize::from(r == 0 && g > 0);
+            }
+        }
+    }
+    assert!(red > 0, "error line was never drawn red");
+    assert!(green > 0, "info line was not drawn in the rain colors");
+}
+
+#[test]
+fn follows_new_lines_only() {
+    let path = temp_log("follow");
+    append(&path, "old line\n");
+    let mut feed = follow(&path);
+    append(&path, "first\nsecond\n");
+    let lines = receive(&mut feed, 2);
+    fs::remove_file(&path).unwrap();
+    assert_eq!(lines, ["first\n", "second\n"]);
+}
+
+#[test]
+fn starts_over_after_truncation() {
+    let path = temp_log("truncate");
+    append(&path, "a long line before truncation\n");
+    let mut feed = follow(&path);
+    append(&path, "before\n");
+    receive(&mut feed, 1);
+
+    fs::write(&path, "after\n").unwrap();
+    let lines = receive(&mut feed, 1);
+    fs::remove_file(&path).unwrap();
+    assert_eq!(lines, ["after\n"]);
+}
+
+#[test]
+fn follows_rotated_files() {
+    let path = temp_log("rotate");
+    let rotated = path.with_extension("log.1");
+    append(&path, "");
+    let mut feed = follow(&path);
+
+    // Lines written just before the rotation are still read from the old file
+    append(&path, "last old line\n");
+    fs::rename(&path, &rotated).unwrap();
+    append(&path, "first new line\n");
+    let lines = receive(&mut feed, 2);
+    fs::remove_file(&path).unwrap();
+    fs::remove_file(&rotated).unwrap();
+    assert_eq!(lines, ["last old line\n", "first new line\n"]);
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use common::VirtualTerminal;
+use crossterm::style::Color;
+use make_it_rain::{themes, MatrixConfig, MatrixSimulation};
+
+fn rgb((r, g, b): (u8, u8, u8)) -> Color {
+    Color::Rgb { r, g, b }
+}
+
+fn run(config: MatrixConfig, frames: u32) -> MatrixSimulation {
+    let mut term = VirtualTerminal::new(config.with_seed(Some(5)).with_rgb_fade(true), 24, 12);
+    term.step_frames(frames);
+    term.simulation
+}
+
+#[test]
+fn looks_up_themes_by_name() {
+    for theme in themes::THEMES {
+        assert_eq!(themes::find_theme(theme.name).unwrap().name, theme.name);
+    }
+    assert_eq!(themes::find_theme(" Ice_Blue ").unwrap().name, "ice-blue");
+    assert!(themes::find_theme("plaid").is_none());
+
+    let err = themes::parse_theme("plaid").unwrap_err();
+    assert!(err.contains("synthwave") && err.contains("colorblind-safe"));
+}
+
+#[test]
+fn every_theme_sets_every_color() {
+    for theme in themes::THEMES {
+        let config = theme.apply(MatrixConfig::new());
+        assert_eq!(config.head_color(), Some(rgb(theme.head)));
+        assert_eq!(config.sticky_color(), Some(rgb(theme.sticky)));
+        assert_eq!(config.glitch_color(), Some(rgb(theme.glitch)));
+        assert_eq!(config.gradient(), Some(&theme.gradient()));
+    }
+}
+
+#[test]
+fn heads_use_the_theme_head_color() {
+    let theme = themes::find_theme("synthwave").unwrap();
+    let config = theme
+        .apply(MatrixConfig::new())
+        .with_glitch_probability(0.0)
+        .with_flicker_probability(0.0)
+        .with_stuck(false);
+    let simulation = run(config, 120);
+
+    let head = rgb(theme.head);
+    let heads = simulation.frame().iter().filter(|(_, _, cell)| cell.color == head).count();
+    assert!(heads > 0, "no drop heads in the theme's head color");
+}
+
+#[test]
+fn glitches_use_the_theme_glitch_color() {
+    let theme = themes::find_theme("amber").unwrap();
+    let config = theme
+        .apply(MatrixConfig::new())
+        .with_glitch_probability(1.0)
+        .with_flicker_probability(0.0)
+        .with_stuck(false);
+    let simulation = run(config, 120);
+
+    let glitch = rgb(theme.glitch);
+    assert!(simulation.frame().iter().count() > 0);
+    assert!(simulation.frame().iter().all(|(_, _, cell)| cell.color == glitch));
+}
+
+#[test]
+fn stuck_characters_use_the_theme_sticky_color() {
+    let theme = themes::find_theme("colorblind-safe").unwrap();
+    let config = theme
+        .apply(MatrixConfig::new())
+        .with_stuck_probability(1.0)
+        .with_glitch_probability(0.0)
+        .with_flicker_probability(0.0);
+    let simulation = run(config, 300);
+
+    let sticky = rgb(theme.sticky);
+    assert!(simulation.frame().iter().any(|(_, _, cell)| cell.color == sticky));
+}
@@ -0,0 +1,77 @@
+#![cfg(feature = "ratatui")]
+
+use std::thread::sleep;
+use std::time::Duration;
+
+use make_it_rain::{scheduler::SIMULATION_STEP, MatrixConfig, MatrixRainState, MatrixRainWidget};
+use ratatui::{buffer::Buffer, layout::Rect, widgets::StatefulWidget};
+
+fn state() -> MatrixRainState {
+    MatrixRainState::new(MatrixConfig::new().with_seed(Some(6)).with_initial_drops(12))
+}
+
+fn cells(state: &MatrixRainState) -> Vec<char> {
+    let frame = state.simulation().unwrap().frame();
+    frame.cells().iter().map(|cell| cell.glyph).collect()
+}
+
+#[test]
+fn draws_only_inside_its_area() {
+    let mut buf = Buffer::empty(Rect::new(0, 0, 30, 16));
+    let blank = buf.clone();
+    let area = Rect::new(6, 4, 12, 8);
+    let mut state = state();
+    for _ in 0..60 {
+        MatrixRainWidget::manual().render(area, &mut buf, &mut state);
+        state.tick(SIMULATION_STEP * 2);
+    }
+
+    let mut drawn = 0;
+    for y in 0..buf.area.height {
+        for x in 0..buf.area.width {
+            if area.contains((x, y).into()) {
+                drawn += usize::from(buf[(x, y)] != blank[(x, y)]);
+            } else {
+                assert_eq!(buf[(x, y)], blank[(x, y)], "cell ({x}, {y}) outside the area was written");
+            }
+        }
+    }
+    assert!(drawn > 0, "nothing was drawn inside the area");
+}
+
+#[test]
+fn follows_the_area_size() {
+    let mut buf = Buffer::empty(Rect::new(0, 0, 30, 16));
+    let mut state = state();
+    assert!(state.simulation().is_none());
+
+    MatrixRainWidget::new().render(Rect::new(6, 4, 12, 8), &mut buf, &mut state);
+    assert_eq!(state.simulation().unwrap().size(), (12, 8));
+
+    MatrixRainWidget::new().render(Rect::new(2, 1, 20, 10), &mut buf, &mut state);
+    assert_eq!(state.simulation().unwrap().size(), (20, 10));
+
+    // Areas reaching past the buffer are cut to fit it
+    MatrixRainWidget::new().render(Rect::new(20, 10, 20, 10), &mut buf, &mut state);
+    assert_eq!(state.simulation().unwrap().size(), (10, 6));
+}
+
+#[test]
+fn manual_state_waits_for_tick() {
+    let mut buf = Buffer::empty(Rect::new(0, 0, 30, 16));
+    let area = Rect::new(6, 4, 12, 8);
+    let mut state = state();
+    MatrixRainWidget::manual().render(area, &mut buf, &mut state);
+    state.tick(SIMULATION_STEP * 120);
+    MatrixRainWidget::manual().render(area, &mut buf, &mut state);
+    let first = cells(&state);
+    assert!(first.iter().any(|&glyph| glyph != ' '));
+
+    sleep(Duration::from_millis(50));
+    MatrixRainWidget::manual().render(area, &mut buf, &mut state);
+    assert_eq!(cells(&state), first);
+
+    state.tick(SIMULATION_STEP * 30);
+    MatrixRainWidget::manual().render(area, &mut buf, &mut state);
+    assert_ne!(cells(&state), first);
+}
@@ -0,0 +1,73 @@
+mod common;
+
+use std::fs;
+
+use common::column;
+use make_it_rain::{scheduler::SIMULATION_STEP, MatrixConfig, MatrixSimulation, Wordlist};
+
+#[test]
+fn parses_inline_lists() {
+    let words = Wordlist::parse("neo, trinity ,,the oracle").unwrap();
+    let words: Vec<String> = words.words().iter().map(|word| word.iter().collect()).collect();
+    assert_eq!(words, ["neo", "trinity", "the oracle"]);
+    assert!(Wordlist::parse(" , ").is_err());
+}
+
+#[test]
+fn reads_wordlist_files() {
+    let path = std::env::temp_dir().join(format!("mir-words-{}.txt", std::process::id()));
+    fs::write(&path, "# crew\nneo\n\n  morpheus  \n").unwrap();
+    let words = Wordlist::from_file(&path);
+    fs::remove_file(&path).unwrap();
+
+    let words = words.unwrap();
+    assert_eq!(words.words().len(), 2);
+    assert_eq!(words.longest(), 8);
+    assert!(Wordlist::from_file(&std::env::temp_dir().join("mir-no-such-words.txt")).is_err());
+}
+
+#[test]
+fn drops_spell_their_word_behind_the_head() {
+    let config = MatrixConfig::new()
+        .with_seed(Some(21))
+        .with_initial_drops(8)
+        .with_new_drop_probability(0.0)
+        .with_glitch_probability(0.0)
+        .with_flicker_probability(0.0)
+        .with_stuck(false)
+        .with_words(Some(Wordlist::parse("ZION,NEBUCHADNEZZAR").unwrap()));
+    let mut simulation = MatrixSimulation::new(config, 8, 30);
+
+    // The words must stay readable however long the drops have been falling
+    let mut seen = 0;
+    for _ in 0..12 {
+        for _ in 0..10 {
+            simulation.tick(SIMULATION_STEP);
+        }
+        seen += (0..8)
+            .map(|x| column(&simulation, x))
+            .filter(|text| text.contains("ZION") || text.contains("NEBUCHADNEZZAR"))
+            .count();
+    }
+    assert!(seen > 0, "no word was spelled out");
+}
+
+#[test]
+fn long_phrases_stretch_the_trail() {
+    let config = MatrixConfig::new()
+        .with_seed(Some(22))
+        .with_initial_drops(1)
+        .with_max_trail(4)
+        .with_new_drop_probability(0.0)
+        .with_glitch_probability(0.0)
+        .with_flicker_probability(0.0)
+        .with_words(Some(Wordlist::parse("WAKE UP NEO").unwrap()));
+    let mut simulation = MatrixSimulation::new(config, 1, 40);
+    for _ in 0..200 {
+        simulation.tick(SIMULATION_STEP);
+        if column(&simulation, 0).contains("WAKE UP NEO") {
+            return;
+        }
+    }
+    panic!("the phrase never showed up in full");
+}